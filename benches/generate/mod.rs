@@ -0,0 +1,301 @@
+//! Deterministic generators for large, well-formed puzzle inputs.
+//!
+//! Every generator takes a `size` roughly proportional to the number of lines
+//! it produces and always returns the same input for the same size, so results
+//! stay comparable between benchmark runs.
+
+use std::fmt::Write;
+
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+fn rng(day: usize) -> StdRng {
+    StdRng::seed_from_u64(2022 + day as u64)
+}
+
+/// Generates an input for `day`, or `None` if no generator exists for it.
+pub fn input(day: usize, size: usize) -> Option<String> {
+    let generator = match day {
+        1 => day_one,
+        2 => day_two,
+        3 => day_three,
+        4 => day_four,
+        5 => day_five,
+        6 => day_six,
+        7 => day_seven,
+        8 => day_eight,
+        9 => day_nine,
+        10 => day_ten,
+        11 => day_eleven,
+        _ => return None,
+    };
+
+    Some(generator(size))
+}
+
+pub fn day_one(size: usize) -> String {
+    let mut rng = rng(1);
+    let mut input = String::new();
+
+    for elf in 0..size {
+        if elf > 0 {
+            input.push('\n');
+        }
+        for _ in 0..rng.gen_range(1..=10) {
+            writeln!(input, "{}", rng.gen_range(1_000..=60_000)).unwrap();
+        }
+    }
+
+    input
+}
+
+pub fn day_two(size: usize) -> String {
+    let mut rng = rng(2);
+    let mut input = String::new();
+
+    for _ in 0..size {
+        let opponent = *[b'A', b'B', b'C'].choose(&mut rng).unwrap() as char;
+        let me = *[b'X', b'Y', b'Z'].choose(&mut rng).unwrap() as char;
+        writeln!(input, "{opponent} {me}").unwrap();
+    }
+
+    input
+}
+
+/// Every rucksack has exactly one item shared between its halves and every
+/// group of three has exactly one badge.
+pub fn day_three(size: usize) -> String {
+    let mut rng = rng(3);
+    let mut items: Vec<char> = ('a'..='z').chain('A'..='Z').collect();
+    let mut input = String::new();
+
+    for _ in 0..size.div_ceil(3) {
+        items.shuffle(&mut rng);
+        let (badge, rest) = items.split_first().unwrap();
+        let (duplicates, pools) = rest.split_at(3);
+
+        for (line, duplicate) in duplicates.iter().enumerate() {
+            let left_pool = &pools[line * 16..line * 16 + 8];
+            let right_pool = &pools[line * 16 + 8..line * 16 + 16];
+            let half = rng.gen_range(4..=16);
+
+            let mut left = vec![*badge, *duplicate];
+            left.extend((2..half).map(|_| *left_pool.choose(&mut rng).unwrap()));
+            left.shuffle(&mut rng);
+
+            let mut right = vec![*duplicate];
+            right.extend((1..half).map(|_| *right_pool.choose(&mut rng).unwrap()));
+            right.shuffle(&mut rng);
+
+            input.extend(left);
+            input.extend(right);
+            input.push('\n');
+        }
+    }
+
+    input
+}
+
+pub fn day_four(size: usize) -> String {
+    let mut rng = rng(4);
+    let mut input = String::new();
+
+    for _ in 0..size {
+        let mut assignment = || {
+            let start = rng.gen_range(1..=99);
+            (start, rng.gen_range(start..=99))
+        };
+        let (a, b) = assignment();
+        let (c, d) = assignment();
+        writeln!(input, "{a}-{b},{c}-{d}").unwrap();
+    }
+
+    input
+}
+
+/// Nine stacks followed by `size` moves, simulated so that every move only
+/// takes crates that exist.
+pub fn day_five(size: usize) -> String {
+    const STACKS: usize = 9;
+
+    let mut rng = rng(5);
+    let mut stacks: Vec<Vec<char>> = (0..STACKS)
+        .map(|_| {
+            (0..rng.gen_range(1..=8))
+                .map(|_| rng.gen_range('A'..='Z'))
+                .collect()
+        })
+        .collect();
+
+    let mut input = String::new();
+    let height = stacks.iter().map(Vec::len).max().unwrap();
+    for row in (0..height).rev() {
+        let row = stacks
+            .iter()
+            .map(|stack| match stack.get(row) {
+                Some(item) => format!("[{item}]"),
+                None => "   ".to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        writeln!(input, "{row}").unwrap();
+    }
+    let footer = (1..=STACKS)
+        .map(|column| format!(" {column} "))
+        .collect::<Vec<_>>()
+        .join(" ");
+    writeln!(input, "{footer}\n").unwrap();
+
+    for _ in 0..size {
+        let from = loop {
+            let from = rng.gen_range(0..STACKS);
+            if !stacks[from].is_empty() {
+                break from;
+            }
+        };
+        let to = (from + rng.gen_range(1..STACKS)) % STACKS;
+        let quantity = rng.gen_range(1..=stacks[from].len().min(5));
+
+        let split = stacks[from].len() - quantity;
+        let mut moved = stacks[from].split_off(split);
+        stacks[to].append(&mut moved);
+
+        writeln!(input, "move {quantity} from {} to {}", from + 1, to + 1).unwrap();
+    }
+
+    input
+}
+
+/// A long stream without any four distinct characters in a row, ending with a
+/// 26 character marker so every window size has to scan the whole input.
+pub fn day_six(size: usize) -> String {
+    let mut rng = rng(6);
+    let mut input: String = (0..size)
+        .map(|_| *['a', 'b', 'c'].choose(&mut rng).unwrap())
+        .collect();
+    input.extend('a'..='z');
+    input
+}
+
+pub fn day_seven(size: usize) -> String {
+    fn directory(
+        rng: &mut StdRng,
+        name: &str,
+        depth: usize,
+        remaining: &mut usize,
+        max_file_size: u32,
+        out: &mut String,
+    ) {
+        writeln!(out, "$ cd {name}\n$ ls").unwrap();
+
+        let subdirectories = if depth < 6 && *remaining > 0 {
+            rng.gen_range(0..=4)
+        } else {
+            0
+        };
+        let subdirectories: Vec<String> = (0..subdirectories)
+            .map(|index| format!("{}{index}", name.trim_start_matches('/')))
+            .collect();
+        for subdirectory in &subdirectories {
+            writeln!(out, "dir {subdirectory}").unwrap();
+        }
+
+        for index in 0..rng.gen_range(1..=8) {
+            if *remaining == 0 {
+                break;
+            }
+            *remaining -= 1;
+            let size = rng.gen_range(1..=max_file_size);
+            writeln!(out, "{size} f{index}.txt").unwrap();
+        }
+
+        for subdirectory in &subdirectories {
+            directory(rng, subdirectory, depth + 1, remaining, max_file_size, out);
+            writeln!(out, "$ cd ..").unwrap();
+        }
+    }
+
+    let mut rng = rng(7);
+    let mut input = String::new();
+    // Keeps the total used space below the 70_000_000 disk size.
+    let max_file_size = (60_000_000 / size.max(1)).max(1) as u32;
+    let mut remaining = size;
+
+    directory(&mut rng, "/", 0, &mut remaining, max_file_size, &mut input);
+    input
+}
+
+pub fn day_eight(size: usize) -> String {
+    let mut rng = rng(8);
+    let mut input = String::new();
+
+    for _ in 0..size {
+        input.extend((0..size).map(|_| char::from(b'0' + rng.gen_range(0..10))));
+        input.push('\n');
+    }
+
+    input
+}
+
+pub fn day_nine(size: usize) -> String {
+    let mut rng = rng(9);
+    let mut input = String::new();
+
+    for _ in 0..size {
+        let direction = ['U', 'D', 'L', 'R'].choose(&mut rng).unwrap();
+        writeln!(input, "{direction} {}", rng.gen_range(1..=20)).unwrap();
+    }
+
+    input
+}
+
+pub fn day_ten(size: usize) -> String {
+    let mut rng = rng(10);
+    let mut input = String::new();
+
+    for _ in 0..size.max(240) {
+        if rng.gen_bool(0.3) {
+            writeln!(input, "noop").unwrap();
+        } else {
+            writeln!(input, "addx {}", rng.gen_range(-5..=5)).unwrap();
+        }
+    }
+
+    input
+}
+
+/// `size` items spread over eight monkeys. Operations never square the worry
+/// level, so part one cannot overflow without the modulus trick.
+pub fn day_eleven(size: usize) -> String {
+    const MONKEYS: usize = 8;
+    const DIVISORS: [u32; MONKEYS] = [2, 3, 5, 7, 11, 13, 17, 19];
+
+    let mut rng = rng(11);
+    let mut input = String::new();
+
+    for (monkey, divisor) in DIVISORS.iter().enumerate() {
+        if monkey > 0 {
+            input.push('\n');
+        }
+
+        let items = (0..size / MONKEYS)
+            .map(|_| rng.gen_range(50..100).to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let operation = if rng.gen_bool(0.5) {
+            format!("old + {}", rng.gen_range(1..=8))
+        } else {
+            "old * 2".to_string()
+        };
+        let pass_dest = (monkey + rng.gen_range(1..MONKEYS)) % MONKEYS;
+        let fail_dest = (monkey + rng.gen_range(1..MONKEYS)) % MONKEYS;
+
+        writeln!(input, "Monkey {monkey}:").unwrap();
+        writeln!(input, "  Starting items: {items}").unwrap();
+        writeln!(input, "  Operation: new = {operation}").unwrap();
+        writeln!(input, "  Test: divisible by {divisor}").unwrap();
+        writeln!(input, "    If true: throw to monkey {pass_dest}").unwrap();
+        writeln!(input, "    If false: throw to monkey {fail_dest}").unwrap();
+    }
+
+    input
+}
@@ -0,0 +1,139 @@
+//! Benchmarks parsing and both parts of every registered day, on the real
+//! inputs in `inputs/` as well as on generated large inputs.
+//!
+//! HTML reports are written to `target/criterion/report/index.html`. To
+//! compare two commits, save a named baseline on the first one and compare
+//! against it on the second:
+//!
+//! ```text
+//! cargo bench -- --save-baseline before
+//! git checkout <other commit>
+//! cargo bench -- --baseline before
+//! ```
+
+use std::{fs::read_to_string, hint::black_box};
+
+use aoc_2022::{
+    day_eight, day_eleven, day_five, day_four, day_nine, day_one, day_seven, day_ten, day_two,
+};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use itertools::Itertools;
+use nom::combinator::iterator;
+
+mod generate;
+
+/// Size passed to the generator of each day, picked so a single iteration of
+/// the slowest part stays well below a second.
+fn generated_size(day: usize) -> usize {
+    match day {
+        6 => 1_000_000,
+        7 => 10_000,
+        8 => 200,
+        9 => 50_000,
+        11 => 800,
+        _ => 100_000,
+    }
+}
+
+/// Parser benchmarked for `day`. Results are passed through `black_box` so
+/// parsing cannot be optimized away.
+fn parser(day: usize) -> Option<fn(&str)> {
+    let parser: fn(&str) = match day {
+        1 => |input| {
+            black_box(day_one::parse_input(input).unwrap());
+        },
+        2 => |input| {
+            black_box(day_two::parse_rounds(input).unwrap());
+            black_box(day_two::parse_desired_outcomes(input).unwrap());
+        },
+        4 => |input| {
+            black_box(day_four::parse(input).unwrap());
+        },
+        5 => |input| {
+            black_box(day_five::parse(input).unwrap());
+        },
+        7 => |input| {
+            black_box(day_seven::parse_directory(input).unwrap());
+        },
+        8 => |input| {
+            black_box(day_eight::parse(input).unwrap());
+        },
+        9 => |input| {
+            black_box(iterator(input, day_nine::parse_instruction).count());
+        },
+        10 => |input| {
+            black_box(iterator(input, day_ten::parse_instruction).count());
+        },
+        11 => |input| {
+            black_box(day_eleven::parse(input).unwrap());
+        },
+        // Days 3 and 6 work on the raw lines, there is nothing to parse.
+        _ => return None,
+    };
+
+    Some(parser)
+}
+
+fn solutions(c: &mut Criterion) {
+    let solutions = aoc_2022::solutions();
+
+    for day in solutions.keys().copied().sorted() {
+        let solution = solutions[&day];
+        let inputs = [
+            (
+                "real",
+                read_to_string(format!("./inputs/day{day}.txt")).ok(),
+            ),
+            ("generated", generate::input(day, generated_size(day))),
+        ];
+
+        let mut group = c.benchmark_group(format!("day{day}"));
+        group.sample_size(10);
+
+        for (name, input) in inputs {
+            let Some(input) = input else { continue };
+            let input = input.as_str();
+
+            if let Some(parse) = parser(day) {
+                group.bench_with_input(BenchmarkId::new("parse", name), input, |b, input| {
+                    b.iter(|| parse(black_box(input)))
+                });
+            }
+            group.bench_with_input(BenchmarkId::new("part_one", name), input, |b, input| {
+                b.iter(|| solution.part_one(black_box(input)))
+            });
+            group.bench_with_input(BenchmarkId::new("part_two", name), input, |b, input| {
+                b.iter(|| solution.part_two(black_box(input)))
+            });
+        }
+
+        group.finish();
+    }
+}
+
+/// Day 1's part two on a single thread and on all of them.
+#[cfg(feature = "rayon")]
+fn day_one_parallel(c: &mut Criterion) {
+    let input = generate::day_one(1_000_000);
+
+    let mut group = c.benchmark_group("day1_parallel");
+    group.sample_size(10);
+
+    group.bench_with_input("sequential", input.as_str(), |b, input| {
+        b.iter(|| {
+            let (_, elves) = day_one::parse_input(black_box(input)).unwrap();
+            day_one::top_calories(&elves, 3)
+        })
+    });
+    group.bench_with_input("parallel", input.as_str(), |b, input| {
+        b.iter(|| day_one::par_top_calories::<3>(black_box(input)))
+    });
+
+    group.finish();
+}
+
+#[cfg(not(feature = "rayon"))]
+criterion_group!(benches, solutions);
+#[cfg(feature = "rayon")]
+criterion_group!(benches, solutions, day_one_parallel);
+criterion_main!(benches);
@@ -0,0 +1,110 @@
+//! Benchmarks for the shared utilities, compared against the naive approach
+//! they replace. See `benches/solutions.rs` for comparing runs across commits.
+
+use std::hint::black_box;
+
+use aoc_2022::{
+    day_six::find_unique_sequence,
+    day_three::{find_common_items, ItemSet},
+    limit_heap::{ArrayLimitHeap, LimitHeap},
+};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+#[allow(dead_code)]
+mod generate;
+
+fn limit_heap(c: &mut Criterion) {
+    let mut rng = StdRng::seed_from_u64(0);
+    let values: Vec<u32> = (0..1_000_000).map(|_| rng.gen()).collect();
+
+    let mut group = c.benchmark_group("limit_heap");
+
+    macro_rules! top_n {
+        ($($n:literal),*) => {$(
+            group.bench_with_input(BenchmarkId::new("push", $n), &values, |b, values| {
+                b.iter(|| {
+                    let mut heap = LimitHeap::<u32, $n>::new();
+                    for value in values {
+                        heap.push(*value);
+                    }
+                    heap.iter().copied().max()
+                })
+            });
+            group.bench_with_input(BenchmarkId::new("heapless", $n), &values, |b, values| {
+                b.iter(|| {
+                    let mut heap = ArrayLimitHeap::<u32, $n>::new();
+                    for value in values {
+                        heap.push(*value);
+                    }
+                    heap.iter().copied().max()
+                })
+            });
+            group.bench_with_input(BenchmarkId::new("sort", $n), &values, |b, values| {
+                b.iter(|| {
+                    let mut sorted = values.clone();
+                    sorted.sort_unstable_by(|a, b| b.cmp(a));
+                    sorted.truncate($n);
+                    sorted.first().copied()
+                })
+            });
+        )*};
+    }
+
+    top_n!(3, 10, 100);
+    group.finish();
+}
+
+fn unique_sequence(c: &mut Criterion) {
+    let input = generate::day_six(1_000_000);
+
+    let mut group = c.benchmark_group("find_unique_sequence");
+
+    macro_rules! window {
+        ($($n:literal),*) => {$(
+            group.bench_with_input(BenchmarkId::from_parameter($n), input.as_str(), |b, input| {
+                b.iter(|| find_unique_sequence::<$n>(black_box(input)))
+            });
+        )*};
+    }
+
+    window!(4, 8, 14, 20, 26);
+    group.finish();
+}
+
+/// Items shared by both compartments of every rucksack.
+fn item_sets(c: &mut Criterion) {
+    let input = generate::day_three(100_000);
+    let rucksacks: Vec<(&str, &str)> = input
+        .lines()
+        .map(|rucksack| rucksack.split_at(rucksack.len() / 2))
+        .collect();
+
+    let mut group = c.benchmark_group("item_sets");
+
+    group.bench_with_input("hash_set", &rucksacks, |b, rucksacks| {
+        b.iter(|| {
+            rucksacks
+                .iter()
+                .map(|(left, right)| find_common_items([left.chars(), right.chars()]).len())
+                .sum::<usize>()
+        })
+    });
+    group.bench_with_input("bitset", &rucksacks, |b, rucksacks| {
+        b.iter(|| {
+            rucksacks
+                .iter()
+                .map(|(left, right)| {
+                    let left = ItemSet::parse(1, black_box(left)).unwrap();
+                    let right = ItemSet::parse(1, black_box(right)).unwrap();
+                    left.intersection(right).len()
+                })
+                .sum::<usize>()
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, limit_heap, unique_sequence, item_sets);
+criterion_main!(benches);
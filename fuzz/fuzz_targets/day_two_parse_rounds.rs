@@ -0,0 +1,8 @@
+#![no_main]
+
+use aoc_2022::day_two;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let _ = day_two::parse_rounds(input);
+});
@@ -0,0 +1,19 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// The first byte picks the day, the rest is its input. Every part must
+// return an answer or an error, never panic.
+fuzz_target!(|data: &[u8]| {
+    let Some((day, input)) = data.split_first() else {
+        return;
+    };
+    let Ok(input) = std::str::from_utf8(input) else {
+        return;
+    };
+
+    if let Some(solution) = aoc_2022::solutions().get(&usize::from(*day)) {
+        let _ = solution.part_one(input);
+        let _ = solution.part_two(input);
+    }
+});
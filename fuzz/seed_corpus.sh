@@ -0,0 +1,27 @@
+#!/bin/sh
+# Seeds the fuzzing corpus from the puzzle inputs so fuzzing can run offline:
+#
+#   ./seed_corpus.sh && cargo fuzz run solutions
+set -e
+cd "$(dirname "$0")"
+
+seed() {
+    mkdir -p "corpus/$1"
+    cp "../inputs/day$2.txt" "corpus/$1/day$2.txt"
+}
+
+seed day_one_parse_input 1
+seed day_two_parse_rounds 2
+seed day_five_parse 5
+seed day_seven_parse_directory 7
+seed day_ten_parse_instruction 10
+seed day_eleven_parse 11
+
+mkdir -p corpus/solutions
+for input in ../inputs/day*.txt; do
+    day=$(basename "$input" .txt | sed 's/^day//')
+    {
+        printf "\\$(printf '%03o' "$day")"
+        cat "$input"
+    } > "corpus/solutions/day$day.txt"
+done
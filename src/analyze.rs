@@ -0,0 +1,104 @@
+//! Reports about a day's input that go beyond the puzzle answers.
+
+use crate::{
+    day_five::{
+        self,
+        optimize::{optimize, Goal},
+        CrateMover9000, CrateMover9001,
+    },
+    day_four, day_one, day_three, day_two,
+    error::parse_all,
+    Error,
+};
+
+/// States the day 5 procedure optimiser may expand per search.
+const OPTIMIZER_LIMIT: usize = 100;
+
+/// Settings of the reports that have any.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Options {
+    /// Number of day 1 elves carrying the most calories to list.
+    pub top: usize,
+    /// Number of rucksacks in each day 3 group.
+    pub group_size: usize,
+    /// Number of compartments each day 3 rucksack is split into.
+    pub compartments: usize,
+    /// Number of compartments a day 3 item must be in to count as shared.
+    pub at_least: usize,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            top: 3,
+            group_size: 3,
+            compartments: 2,
+            at_least: 2,
+        }
+    }
+}
+
+/// The report about `input` for `day`, or `None` if the day has no analysis.
+pub fn report(day: usize, input: &str, options: &Options) -> Result<Option<String>, Error> {
+    let report = match day {
+        1 => {
+            let elves = day_one::parse(input)?;
+            day_one::analyze(&elves, options.top)?.to_string()
+        }
+        2 => {
+            let game = day_two::Game::rock_paper_scissors();
+            let guide = parse_all(input, day_two::parse_game_rounds(&game))?;
+            let outcomes = parse_all(input, day_two::parse_game_desired_outcomes(&game))?;
+            let plans = [
+                ("choices", guide.clone()),
+                (
+                    "outcomes",
+                    outcomes
+                        .into_iter()
+                        .map(|round| game.apply_strategy(round))
+                        .collect(),
+                ),
+            ];
+
+            format!(
+                "{}\n{}",
+                day_two::explore(&game, &guide)?,
+                day_two::simulation::report(&game, &plans, 100, 2022)
+            )
+        }
+        3 => {
+            let groups = day_three::groups(input, options.group_size)?;
+            let breakdown = day_three::breakdown(input, options.compartments, options.at_least)?;
+            let lines = groups
+                .iter()
+                .map(ToString::to_string)
+                .chain(breakdown.iter().map(ToString::to_string));
+            lines.map(|line| line + "\n").collect()
+        }
+        4 => {
+            let pairs = parse_all(input, day_four::parse)?;
+            format!(
+                "{}\n{}",
+                day_four::classify(&pairs),
+                day_four::index::SectionIndex::new(&pairs)
+            )
+        }
+        5 => {
+            let (stacks, procedure) = parse_all(input, day_five::parse)?;
+            let mut report = String::new();
+            for goal in [Goal::Tops, Goal::Layout] {
+                let optimizations = [
+                    optimize(&CrateMover9000, &stacks, &procedure, goal, OPTIMIZER_LIMIT)?,
+                    optimize(&CrateMover9001, &stacks, &procedure, goal, OPTIMIZER_LIMIT)?,
+                ];
+                for optimization in optimizations {
+                    report += &format!("{optimization}\n");
+                }
+            }
+            report
+        }
+        _ => return Ok(None),
+    };
+
+    Ok(Some(report))
+}
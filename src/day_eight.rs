@@ -1,23 +1,41 @@
-use std::{collections::HashSet, ops::Index, thread::current};
+use std::{collections::HashSet, ops::Index};
 
 use itertools::Itertools;
 
-fn parse(input: &str) -> Grid<u32> {
-    let width = input.lines().next().unwrap().len();
-    let cells: Vec<u32> = input
-        .lines()
-        .flat_map(|line| line.chars())
-        .map(|c| c.to_digit(10).unwrap())
-        .collect();
+use crate::Error;
 
-    Grid {
+pub fn parse(input: &str) -> Result<Grid<u32>, Error> {
+    let width = input.lines().next().map(str::len).unwrap_or(0);
+    if width == 0 {
+        return Err(Error::Parse("line 1: grid has no trees".to_string()));
+    }
+
+    let mut cells = Vec::new();
+    for (line, row) in input.lines().zip(1..) {
+        if line.len() != width {
+            return Err(Error::Parse(format!(
+                "line {row}: expected {width} trees, found {}",
+                line.chars().count()
+            )));
+        }
+
+        for c in line.chars() {
+            let height = c
+                .to_digit(10)
+                .ok_or_else(|| Error::Parse(format!("line {row}: `{c}` is not a tree height")))?;
+            cells.push(height);
+        }
+    }
+
+    Ok(Grid {
         width,
         height: cells.len() / width,
         cells,
-    }
+    })
 }
 
-struct Grid<T> {
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Grid<T> {
     height: usize,
     width: usize,
     cells: Vec<T>,
@@ -94,8 +112,8 @@ impl crate::Solution for Solution {
     type O1 = usize;
     type O2 = usize;
 
-    fn part_one(input: &str) -> Self::O1 {
-        let grid = parse(input);
+    fn part_one(input: &str) -> Result<Self::O1, Error> {
+        let grid = parse(input)?;
 
         let left_to_right = grid.rows().flat_map(|row| scan_visibility(&grid, row));
         let right_to_left = grid
@@ -108,16 +126,16 @@ impl crate::Solution for Solution {
             .columns()
             .flat_map(|column| scan_visibility(&grid, column.rev()));
 
-        left_to_right
+        Ok(left_to_right
             .chain(right_to_left)
             .chain(top_to_bottom)
             .chain(bottom_to_top)
             .collect::<HashSet<_>>()
-            .len()
+            .len())
     }
 
-    fn part_two(input: &str) -> Self::O2 {
-        let grid = parse(input);
+    fn part_two(input: &str) -> Result<Self::O2, Error> {
+        let grid = parse(input)?;
         grid.rows()
             .flatten()
             .map(|start| {
@@ -130,7 +148,7 @@ impl crate::Solution for Solution {
                 left_to_right * right_to_left * top_to_bottom * bottom_to_top
             })
             .max()
-            .unwrap()
+            .ok_or_else(|| Error::Invalid("Grid has no trees".to_string()))
     }
 }
 
@@ -140,6 +158,7 @@ mod test {
 
     use crate::Solution;
 
+    #[allow(clippy::redundant_static_lifetimes)]
     const SAMPLE_INPUT: &'static str = indoc! {"
         30373
         25512
@@ -150,22 +169,29 @@ mod test {
 
     #[test]
     fn parse() {
-        let grid = super::parse(SAMPLE_INPUT);
+        let grid = super::parse(SAMPLE_INPUT).unwrap();
         assert_eq!(grid.width, 5);
         assert_eq!(grid.height, 5);
         assert_eq!(grid[(0, 0)], 3);
         assert_eq!(grid[(1, 3)], 1);
     }
 
+    #[test]
+    fn parse_invalid_grid() {
+        assert!(super::parse("").is_err());
+        assert!(super::parse("123\n45\n").is_err());
+        assert!(super::parse("123\n4x6\n").is_err());
+    }
+
     #[test]
     fn part_one() {
         let result = super::Solution::part_one(SAMPLE_INPUT);
-        assert_eq!(result, 21);
+        assert_eq!(result, Ok(21));
     }
 
     #[test]
     fn visible_distance() {
-        let grid = super::parse(SAMPLE_INPUT);
+        let grid = super::parse(SAMPLE_INPUT).unwrap();
         assert_eq!(
             super::visible_distance((1, 2), &grid, grid.column(2).rev()),
             1
@@ -178,6 +204,6 @@ mod test {
     #[test]
     fn part_two() {
         let result = super::Solution::part_two(SAMPLE_INPUT);
-        assert_eq!(result, 8);
+        assert_eq!(result, Ok(8));
     }
 }
@@ -1,4 +1,3 @@
-use itertools::Itertools;
 use nom::{
     branch::alt,
     bytes::complete::tag,
@@ -9,8 +8,11 @@ use nom::{
     IResult,
 };
 
+use crate::{error::parse_all, limit_heap::LimitHeap, Error};
+
 #[derive(Clone, Copy, Debug)]
-enum Expression {
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Expression {
     Old,
     Value(i64),
 }
@@ -25,21 +27,25 @@ impl Expression {
 }
 
 #[derive(Clone, Copy, Debug)]
-enum Operation {
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Operation {
     Add(Expression, Expression),
     Multiply(Expression, Expression),
 }
 
 impl Operation {
-    fn apply(&self, old: i64) -> i64 {
-        match self {
-            Operation::Add(a, b) => a.eval(old).checked_add(b.eval(old)).unwrap(),
-            Operation::Multiply(a, b) => a.eval(old).checked_mul(b.eval(old)).unwrap(),
-        }
+    fn apply(&self, old: i64) -> Result<i64, Error> {
+        let new = match self {
+            Operation::Add(a, b) => a.eval(old).checked_add(b.eval(old)),
+            Operation::Multiply(a, b) => a.eval(old).checked_mul(b.eval(old)),
+        };
+
+        new.ok_or_else(|| Error::Invalid(format!("Worry level overflowed after {old}")))
     }
 }
 
-struct Test {
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Test {
     divisor: i64,
     pass_dest: usize,
     fail_dest: usize,
@@ -55,12 +61,14 @@ impl Test {
     }
 }
 
-struct Monkey {
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Monkey {
     items: Vec<i64>,
     operation: Operation,
     test: Test,
 }
 
+#[allow(clippy::redundant_closure)]
 fn parse_expression(input: &str) -> IResult<&str, Expression> {
     alt((
         value(Expression::Old, tag("old")),
@@ -117,18 +125,60 @@ fn parse_monkey(input: &str) -> IResult<&str, Monkey> {
     })(input)
 }
 
-fn parse(input: &str) -> IResult<&str, Vec<Monkey>> {
+pub fn parse(input: &str) -> IResult<&str, Vec<Monkey>> {
     separated_list0(newline, parse_monkey)(input)
 }
 
+/// Parses the monkeys, checking that every test divides by a positive number
+/// and throws to a monkey that exists.
+fn parse_monkeys(input: &str) -> Result<Vec<Monkey>, Error> {
+    let monkeys = parse_all(input, parse)?;
+
+    for (index, monkey) in monkeys.iter().enumerate() {
+        let test = &monkey.test;
+        if test.divisor <= 0 {
+            return Err(Error::Invalid(format!(
+                "Monkey {index} tests divisibility by {}",
+                test.divisor
+            )));
+        }
+        if let Some(dest) = [test.pass_dest, test.fail_dest]
+            .into_iter()
+            .find(|dest| *dest >= monkeys.len())
+        {
+            return Err(Error::Invalid(format!(
+                "Monkey {index} throws to nonexistent monkey {dest}"
+            )));
+        }
+    }
+
+    Ok(monkeys)
+}
+
+/// Index and inspection count of the `k` most active monkeys, most active
+/// first.
+pub fn most_active(inspection_count: &[u64], k: usize) -> Vec<(usize, u64)> {
+    let mut most_active = LimitHeap::by_key(k, |(_, count): &(usize, u64)| *count);
+    most_active.extend(inspection_count.iter().copied().enumerate());
+    most_active.into_sorted_vec()
+}
+
+/// Product of the `k` largest inspection counts.
+pub fn monkey_business(inspection_count: &[u64], k: usize) -> u64 {
+    most_active(inspection_count, k)
+        .into_iter()
+        .map(|(_, count)| count)
+        .product()
+}
+
 pub struct Solution;
 impl crate::Solution for Solution {
     const DAY: usize = 11;
     type O1 = u64;
     type O2 = u64;
 
-    fn part_one(input: &str) -> Self::O1 {
-        let (_, mut monkeys) = parse(input).unwrap();
+    fn part_one(input: &str) -> Result<Self::O1, Error> {
+        let mut monkeys = parse_monkeys(input)?;
         let mut inspection_count = vec![0; monkeys.len()];
 
         for _ in 0..20 {
@@ -136,35 +186,38 @@ impl crate::Solution for Solution {
                 let items = monkeys[i].items.split_off(0);
                 inspection_count[i] += items.len() as u64;
                 for item in items {
-                    let new_item = monkeys[i].operation.apply(item) / 3;
+                    let new_item = monkeys[i].operation.apply(item)? / 3;
                     let destination = monkeys[i].test.get_destination(new_item);
                     monkeys[destination].items.push(new_item);
                 }
             }
         }
 
-        inspection_count.iter().sorted().rev().take(2).product()
+        Ok(monkey_business(&inspection_count, 2))
     }
 
-    fn part_two(input: &str) -> Self::O2 {
-        let (_, mut monkeys) = parse(input).unwrap();
+    fn part_two(input: &str) -> Result<Self::O2, Error> {
+        let mut monkeys = parse_monkeys(input)?;
         let mut inspection_count = vec![0; monkeys.len()];
 
-        let modulus: i64 = monkeys.iter().map(|m| m.test.divisor).product();
+        let modulus = monkeys
+            .iter()
+            .try_fold(1i64, |modulus, m| modulus.checked_mul(m.test.divisor))
+            .ok_or_else(|| Error::Invalid("Product of divisors overflowed".to_string()))?;
 
         for _ in 0..10000 {
             for i in 0..monkeys.len() {
                 let items = monkeys[i].items.split_off(0);
                 inspection_count[i] += items.len() as u64;
                 for item in items {
-                    let new_item = monkeys[i].operation.apply(item) % modulus;
+                    let new_item = monkeys[i].operation.apply(item)? % modulus;
                     let destination = monkeys[i].test.get_destination(new_item);
                     monkeys[destination].items.push(new_item);
                 }
             }
         }
 
-        inspection_count.iter().sorted().rev().take(2).product()
+        Ok(monkey_business(&inspection_count, 2))
     }
 }
 
@@ -172,6 +225,17 @@ impl crate::Solution for Solution {
 mod test {
     use indoc::indoc;
 
+    #[test]
+    fn most_active() {
+        let inspection_count = [101, 95, 7, 105];
+
+        assert_eq!(
+            super::most_active(&inspection_count, 2),
+            vec![(3, 105), (0, 101)]
+        );
+        assert_eq!(super::monkey_business(&inspection_count, 2), 10605);
+    }
+
     #[test]
     fn parse_operation() {
         let input = indoc! {"
@@ -1,19 +1,226 @@
+use std::fmt::{self, Display};
+
 use nom::{
     branch::alt,
-    bytes::complete::tag,
-    character::complete::{anychar, char, newline},
+    bytes::complete::{tag, take, take_while_m_n},
+    character::complete::{char, newline, space0, space1, u32 as parse_u32},
     combinator::{map, value},
-    multi::{count, fold_many1, separated_list1},
-    sequence::{delimited, preceded, separated_pair, terminated, tuple},
+    multi::{fold_many0, separated_list1},
+    sequence::{delimited, pair, preceded, separated_pair, terminated, tuple},
     IResult,
 };
 
-fn parse_stacks(input: &str) -> IResult<&str, Vec<Vec<char>>> {
-    let item = map(delimited(char('['), anychar, char(']')), |c| Some(c));
-    let gap = value(None, count(anychar, 3));
+use crate::{error::parse_all, Error};
+
+pub mod optimize;
+pub mod replay;
+
+/// The label of a crate, such as `A` in `[A]`.
+pub type Label = String;
+
+/// Numbered stacks of crates, each listed from the bottom up.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(transparent))]
+pub struct Stacks {
+    stacks: Vec<Vec<Label>>,
+    /// Width of the labels in the drawing, in characters.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    width: usize,
+}
+
+/// Quantity of crates, then the stack they are moved from and the stack they
+/// are moved to.
+pub type Move = (u32, u32, u32);
+
+impl Stacks {
+    pub fn len(&self) -> usize {
+        self.stacks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stacks.is_empty()
+    }
+
+    /// Width of the labels in the drawing, in characters.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The crates of stack `number`, counting from 1, from the bottom up.
+    pub fn get(&self, number: u32) -> Option<&[Label]> {
+        let index = (number as usize).checked_sub(1)?;
+        self.stacks.get(index).map(Vec::as_slice)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &[Label]> {
+        self.stacks.iter().map(Vec::as_slice)
+    }
+
+    /// The labels of the crates on top of every stack, with spaces for empty
+    /// stacks.
+    pub fn tops(&self) -> String {
+        let blank = " ".repeat(self.width);
+        self.stacks
+            .iter()
+            .map(|stack| stack.last().unwrap_or(&blank).as_str())
+            .collect()
+    }
+
+    fn stack_mut(&mut self, number: u32) -> Result<&mut Vec<Label>, Error> {
+        (number as usize)
+            .checked_sub(1)
+            .and_then(|index| self.stacks.get_mut(index))
+            .ok_or_else(|| Error::Invalid(format!("stack {number} does not exist")))
+    }
+
+    /// Removes the top `quantity` crates of stack `from`, from the bottom up.
+    /// Nothing is removed unless stack `to` exists too.
+    fn lift(&mut self, (quantity, from, to): Move) -> Result<Vec<Label>, Error> {
+        self.stack_mut(to)?;
+        let stack = self.stack_mut(from)?;
+        let split = stack
+            .len()
+            .checked_sub(quantity as usize)
+            .ok_or_else(|| Error::Invalid(format!("stack {from} has too few crates to move")))?;
+        Ok(stack.split_off(split))
+    }
+}
+
+/// Stacks of the given labels. Shorter labels are padded with spaces to the
+/// longest one when drawn, and the padding is dropped again when parsed.
+impl<L: Into<Label>> From<Vec<Vec<L>>> for Stacks {
+    fn from(stacks: Vec<Vec<L>>) -> Self {
+        let stacks: Vec<Vec<Label>> = stacks
+            .into_iter()
+            .map(|stack| stack.into_iter().map(Into::into).collect())
+            .collect();
+        let width = stacks
+            .iter()
+            .flatten()
+            .map(|label| label.chars().count())
+            .max()
+            .unwrap_or(1);
+
+        Self { stacks, width }
+    }
+}
+
+/// Draws the stacks the way the puzzle input does, padding every row to the
+/// full width and ending with the stack numbers starting below the middle of
+/// each stack. Stack numbers wider than half a crate are not aligned.
+impl Display for Stacks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self.width;
+        let height = self.stacks.iter().map(Vec::len).max().unwrap_or(0);
+
+        for row in (0..height).rev() {
+            let cells: Vec<_> = self
+                .stacks
+                .iter()
+                .map(|stack| match stack.get(row) {
+                    Some(label) => format!("[{label:<width$}]"),
+                    None => " ".repeat(width + 2),
+                })
+                .collect();
+            writeln!(f, "{}", cells.join(" "))?;
+        }
+
+        let middle = width.div_ceil(2);
+        let footer: Vec<_> = (1..=self.stacks.len())
+            .map(|n| format!("{:middle$}{n:<rest$}", "", rest = width + 2 - middle))
+            .collect();
+        writeln!(f, "{}", footer.join(" "))
+    }
+}
+
+/// How a crane moves several crates at once.
+pub trait Crane {
+    fn name(&self) -> &'static str;
+
+    /// Applies a single move to `stacks`, leaving them untouched if it
+    /// fails.
+    fn apply(&self, stacks: &mut Stacks, step: Move) -> Result<(), Error>;
+
+    /// Applies every move of `procedure` in order, stopping at the first one
+    /// that fails.
+    fn run(&self, stacks: &mut Stacks, procedure: &[Move]) -> Result<(), Error> {
+        procedure
+            .iter()
+            .try_for_each(|&step| self.apply(stacks, step))
+    }
+}
+
+/// Moves crates one at a time, so the moved crates end up reversed.
+pub struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn name(&self) -> &'static str {
+        "CrateMover 9000"
+    }
+
+    fn apply(&self, stacks: &mut Stacks, step: Move) -> Result<(), Error> {
+        let mut crates = stacks.lift(step)?;
+        // Moving crates one at a time onto the stack they came from puts
+        // each one back where it was.
+        if step.1 != step.2 {
+            crates.reverse();
+        }
+        stacks.stack_mut(step.2)?.extend(crates);
+        Ok(())
+    }
+}
+
+/// Moves all the crates of a move at once, keeping their order.
+pub struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn name(&self) -> &'static str {
+        "CrateMover 9001"
+    }
+
+    fn apply(&self, stacks: &mut Stacks, step: Move) -> Result<(), Error> {
+        let crates = stacks.lift(step)?;
+        stacks.stack_mut(step.2)?.extend(crates);
+        Ok(())
+    }
+}
+
+/// Width of the crate labels in the drawing at the start of `input`. It is
+/// the width of the first crate, or for a drawing without crates, the width
+/// the stack numbers are spaced for.
+fn label_width(input: &str) -> usize {
+    let drawing = input.lines().take_while(|line| !line.trim().is_empty());
+
+    let mut footer = "";
+    for line in drawing {
+        if let Some((_, rest)) = line.split_once('[') {
+            if let Some((label, _)) = rest.split_once(']') {
+                return label.chars().count().max(1);
+            }
+        }
+        footer = line;
+    }
+
+    // Stacks are drawn `width + 2` characters wide with a space in between.
+    let stacks = footer.split_whitespace().count();
+    let length = footer.trim_end_matches('\r').chars().count();
+    ((length + 1) / stacks.max(1)).saturating_sub(3).max(1)
+}
+
+/// Parses the drawing of the stacks, whatever the width of the labels and
+/// the number of stacks. The last stack of a row may be cut short when the
+/// line has no trailing spaces, and labels never end with a space. The
+/// stack numbers below may be wider than the stacks.
+fn parse_stacks(input: &str) -> IResult<&str, Stacks> {
+    let width = label_width(input);
+    let item = map(
+        delimited(char('['), take(width), char(']')),
+        |label: &str| Some(label.trim_end_matches(' ').to_string()),
+    );
+    let gap = value(None, take_while_m_n(1, width + 2, |c| c == ' '));
     let row = terminated(separated_list1(char(' '), alt((item, gap))), newline);
 
-    let columns = fold_many1(row, Vec::new, |mut acc: Vec<Vec<char>>, row| {
+    let rows = fold_many0(row, Vec::new, |mut acc: Vec<Vec<Label>>, row| {
         if acc.len() < row.len() {
             acc.resize_with(row.len(), Default::default);
         }
@@ -26,75 +233,76 @@ fn parse_stacks(input: &str) -> IResult<&str, Vec<Vec<char>>> {
         acc
     });
 
-    let mut reversed_columns = nom::combinator::map(columns, |mut columns| {
+    let numbers = delimited(space0, separated_list1(space1, parse_u32), space0);
+    let footer = terminated(numbers, newline);
+
+    let mut reversed_columns = map(pair(rows, footer), |(mut columns, numbers)| {
+        // Stacks without any crate only show up in the footer.
+        if columns.len() < numbers.len() {
+            columns.resize_with(numbers.len(), Default::default);
+        }
         for column in columns.iter_mut() {
             column.reverse()
         }
-        columns
+        Stacks {
+            stacks: columns,
+            width,
+        }
     });
 
     reversed_columns(input)
 }
 
-fn parse_procedure(input: &str) -> IResult<&str, Vec<(u8, u8, u8)>> {
-    let quantity = preceded(tag("move "), nom::character::complete::u8);
-    let from = delimited(
-        char(' '),
-        preceded(tag("from "), nom::character::complete::u8),
-        char(' '),
-    );
-    let to = preceded(tag("to "), nom::character::complete::u8);
+fn parse_procedure(input: &str) -> IResult<&str, Vec<Move>> {
+    let quantity = preceded(tag("move "), parse_u32);
+    let from = delimited(char(' '), preceded(tag("from "), parse_u32), char(' '));
+    let to = preceded(tag("to "), parse_u32);
 
     separated_list1(newline, tuple((quantity, from, to)))(input)
 }
 
-fn parse(input: &str) -> IResult<&str, (Vec<Vec<char>>, Vec<(u8, u8, u8)>)> {
+pub fn parse(input: &str) -> IResult<&str, (Stacks, Vec<Move>)> {
     separated_pair(parse_stacks, newline, parse_procedure)(input)
 }
 
+/// The stacks of `input` once `crane` has carried out its procedure.
+pub fn rearrange(input: &str, crane: &impl Crane) -> Result<Stacks, Error> {
+    let (mut stacks, procedure) = parse_all(input, parse)?;
+    crane.run(&mut stacks, &procedure)?;
+    Ok(stacks)
+}
+
 pub struct Solution;
 impl crate::Solution for Solution {
     const DAY: usize = 5;
     type O1 = String;
     type O2 = String;
 
-    fn part_one(input: &str) -> Self::O1 {
-        let (_, (mut stacks, procedure)) = parse(input).unwrap();
-
-        for (quantity, from, to) in procedure {
-            for _ in 0..quantity {
-                let moved_item = stacks[(from - 1) as usize]
-                    .pop()
-                    .expect("Procedure attempted to move nonexisting item");
-                stacks[(to - 1) as usize].push(moved_item);
-            }
-        }
-
-        stacks
-            .iter()
-            .map(|column| column.last().unwrap_or(&' '))
-            .collect()
+    fn part_one(input: &str) -> Result<Self::O1, Error> {
+        rearrange(input, &CrateMover9000).map(|stacks| stacks.tops())
     }
 
-    fn part_two(input: &str) -> Self::O2 {
-        let (_, (mut stacks, procedure)) = parse(input).unwrap();
-
-        for (quantity, from, to) in procedure {
-            let from_stack = &mut stacks[(from - 1) as usize];
-            let mut moved_items = from_stack.split_off(from_stack.len() - (quantity as usize));
-            stacks[(to - 1) as usize].append(&mut moved_items);
-        }
-
-        stacks
-            .iter()
-            .map(|column| column.last().unwrap_or(&' '))
-            .collect()
+    fn part_two(input: &str) -> Result<Self::O2, Error> {
+        rearrange(input, &CrateMover9001).map(|stacks| stacks.tops())
     }
 }
 
 #[cfg(test)]
 mod test {
     use indoc::indoc;
+    use proptest::prelude::*;
+
+    use super::{Crane, CrateMover9000, CrateMover9001, Stacks};
+    use crate::{Error, Solution};
+
+    fn stacks() -> impl Strategy<Value = Stacks> {
+        (1..4usize).prop_flat_map(|width| {
+            let label = prop::collection::vec(prop::char::range('A', 'Z'), 1..=width)
+                .prop_map(String::from_iter);
+            let stack = prop::collection::vec(label, 0..8);
+            prop::collection::vec(stack, 1..15).prop_map(Stacks::from)
+        })
+    }
 
     #[test]
     fn parse_stacks() {
@@ -105,10 +313,95 @@ mod test {
         "};
 
         let (_, stacks) = super::parse_stacks(input).unwrap();
+        assert_eq!(
+            stacks,
+            Stacks::from(vec![vec!['A'], vec!['C', 'B'], vec!['D']])
+        );
+    }
+
+    #[test]
+    fn parse_wide_stacks() {
+        let input = indoc! {"
+                                                         [AB]     
+            [CD]                                         [EF] [GH]
+             1    2    3    4    5    6    7    8    9    10   11 
+
+            move 1000000 from 10 to 11
+        "};
+
+        let (_, (stacks, procedure)) = super::parse(input).unwrap();
+        assert_eq!(stacks.width(), 2);
+        assert_eq!(stacks.len(), 11);
+        assert_eq!(stacks.get(1).unwrap().concat(), "CD");
+        assert_eq!(stacks.get(10).unwrap().concat(), "EFAB");
+        assert_eq!(stacks.tops(), "CD                ABGH");
+        assert_eq!(
+            stacks.to_string(),
+            input.split("\n\n").next().unwrap().to_string() + "\n"
+        );
+        assert_eq!(procedure, vec![(1_000_000, 10, 11)]);
+    }
+
+    #[test]
+    fn parse_many_stacks() {
+        let input = indoc! {"
+                                                    [K]
+            [A] [B] [C] [D] [E] [F] [G] [H] [I] [J] [L]
+             1   2   3   4   5   6   7   8   9   10  11
+
+            move 1 from 11 to 10
+        "};
+
+        let (_, (stacks, procedure)) = super::parse(input).unwrap();
+        assert_eq!(stacks.width(), 1);
+        assert_eq!(stacks.len(), 11);
+        assert_eq!(stacks.tops(), "ABCDEFGHIJK");
+        assert_eq!(
+            stacks.to_string(),
+            input.split("\n\n").next().unwrap().to_string() + "\n"
+        );
+        assert_eq!(procedure, vec![(1, 11, 10)]);
+
+        let (_, stacks) = super::parse_stacks("[A]\n 1    2     3\n").unwrap();
         assert_eq!(stacks.len(), 3);
-        assert_eq!(stacks[0], vec!['A']);
-        assert_eq!(stacks[1], vec!['C', 'B']);
-        assert_eq!(stacks[2], vec!['D']);
+    }
+
+    #[test]
+    fn parse_trimmed_stacks() {
+        let input = "    [D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3\n\nmove 1 from 2 to 1\n";
+
+        let (_, (stacks, _)) = super::parse(input).unwrap();
+        assert_eq!(
+            stacks,
+            Stacks::from(vec![vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']])
+        );
+    }
+
+    #[test]
+    fn parse_mixed_widths() {
+        let stacks = Stacks::from(vec![vec!["A"], vec!["BC", "D"]]);
+        let drawing = stacks.to_string();
+        assert_eq!(
+            drawing,
+            indoc! {"
+                     [D ]
+                [A ] [BC]
+                 1    2  
+            "}
+        );
+
+        let (_, parsed) = super::parse_stacks(&drawing).unwrap();
+        assert_eq!(parsed.get(1).unwrap(), ["A"]);
+        assert_eq!(parsed, stacks);
+    }
+
+    #[test]
+    fn label_width() {
+        assert_eq!(super::label_width("    [D]\n 1   2 \n"), 1);
+        assert_eq!(super::label_width("[ABC]\n  1  \n\n"), 3);
+        assert_eq!(super::label_width(" 1    2  \n\nmove 1 from 1 to 2"), 2);
+        assert_eq!(super::label_width(" 1   2 \n"), 1);
+        assert_eq!(super::label_width(""), 1);
     }
 
     #[test]
@@ -137,13 +430,132 @@ mod test {
 
         let (_, (stacks, procedure)) = super::parse(input).unwrap();
 
-        assert_eq!(stacks.len(), 3);
-        assert_eq!(stacks[0], vec!['A']);
-        assert_eq!(stacks[1], vec!['C', 'B']);
-        assert_eq!(stacks[2], vec!['D']);
+        assert_eq!(
+            stacks,
+            Stacks::from(vec![vec!['A'], vec!['C', 'B'], vec!['D']])
+        );
 
         assert_eq!(procedure.len(), 2);
         assert_eq!(procedure[0], (2, 1, 9));
         assert_eq!(procedure[1], (10, 3, 22));
     }
+
+    #[test]
+    fn invalid_moves() {
+        let input = indoc! {"
+            [A]    
+             1   2 
+
+            move 1 from 2 to 1
+        "};
+        assert_eq!(
+            super::Solution::part_one(input),
+            Err(Error::Invalid(
+                "stack 2 has too few crates to move".to_string()
+            ))
+        );
+
+        let input = indoc! {"
+            [A]    
+             1   2 
+
+            move 1 from 1 to 3
+        "};
+        assert_eq!(
+            super::Solution::part_two(input),
+            Err(Error::Invalid("stack 3 does not exist".to_string()))
+        );
+    }
+
+    #[test]
+    fn render() {
+        let drawing = indoc! {"
+                [B]    
+            [A] [C] [D]
+             1   2   3 
+        "};
+
+        let (_, stacks) = super::parse_stacks(drawing).unwrap();
+        assert_eq!(stacks.to_string(), drawing);
+
+        let input = indoc! {"
+            [A]    
+             1   2 
+
+            move 1 from 1 to 2
+        "};
+        let stacks = super::rearrange(input, &CrateMover9000).unwrap();
+        assert_eq!(stacks.to_string(), "    [A]\n 1   2 \n");
+    }
+
+    #[test]
+    fn cranes() {
+        let stacks = Stacks::from(vec![vec!['A', 'B', 'C'], vec![]]);
+
+        let mut one_at_a_time = stacks.clone();
+        CrateMover9000.apply(&mut one_at_a_time, (2, 1, 2)).unwrap();
+        assert_eq!(one_at_a_time.get(2).unwrap().concat(), "CB");
+        assert_eq!(one_at_a_time.tops(), "AB");
+
+        let mut bulk = stacks.clone();
+        CrateMover9001.apply(&mut bulk, (2, 1, 2)).unwrap();
+        assert_eq!(bulk.get(2).unwrap().concat(), "BC");
+        assert_eq!(bulk.tops(), "AC");
+
+        for crane in [&CrateMover9000 as &dyn Crane, &CrateMover9001] {
+            let mut same = stacks.clone();
+            crane
+                .run(&mut same, &[(3, 1, 1), (2, 1, 1), (0, 2, 1)])
+                .unwrap();
+            assert_eq!(same, stacks);
+        }
+    }
+
+    #[test]
+    fn moves_onto_the_same_stack() {
+        let input = indoc! {"
+            [A]    
+            [B]    
+            [C] [D]
+             1   2 
+
+            move 2 from 1 to 1
+        "};
+        assert_eq!(super::Solution::part_one(input).unwrap(), "AD");
+        assert_eq!(super::Solution::part_two(input).unwrap(), "AD");
+    }
+
+    #[test]
+    fn failed_moves_change_nothing() {
+        let stacks = Stacks::from(vec![vec!['A'], vec!['B']]);
+
+        let mut moved = stacks.clone();
+        assert_eq!(
+            CrateMover9000.apply(&mut moved, (1, 1, 3)),
+            Err(Error::Invalid("stack 3 does not exist".to_string()))
+        );
+        assert_eq!(
+            CrateMover9001.apply(&mut moved, (1, 0, 2)),
+            Err(Error::Invalid("stack 0 does not exist".to_string()))
+        );
+        assert_eq!(
+            CrateMover9000.run(&mut moved, &[(1, 1, 2), (3, 2, 1)]),
+            Err(Error::Invalid(
+                "stack 2 has too few crates to move".to_string()
+            ))
+        );
+        assert_eq!(moved, Stacks::from(vec![vec![], vec!['B', 'A']]));
+    }
+
+    proptest! {
+        #[test]
+        fn stacks_round_trip(stacks in stacks()) {
+            let drawing = stacks.to_string();
+            let (rest, parsed) = super::parse_stacks(&drawing).unwrap();
+
+            prop_assert_eq!(rest, "");
+            prop_assert_eq!(&parsed, &stacks);
+            prop_assert_eq!(parsed.to_string(), drawing);
+        }
+    }
 }
@@ -0,0 +1,439 @@
+//! Searches for shorter procedures that leave the stacks the same way.
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    fmt::{self, Display},
+};
+
+use super::{Crane, Move, Stacks};
+use crate::Error;
+
+/// What a shorter procedure has to leave as the original one does.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Goal {
+    /// The crate on top of every stack.
+    Tops,
+    /// Every crate of every stack.
+    Layout,
+}
+
+impl Goal {
+    /// A lower bound of the number of moves from `stacks` to `target`, which
+    /// is 0 only once the goal is reached.
+    ///
+    /// A move only changes the stack it takes from and the stack it puts on,
+    /// so the bound drops by at most one per move.
+    fn estimate(self, stacks: &Stacks, target: &Stacks) -> usize {
+        let pairs = stacks.iter().zip(target.iter());
+
+        match self {
+            Goal::Tops => pairs
+                .filter(|(stack, target)| stack.last() != target.last())
+                .count()
+                .div_ceil(2),
+            Goal::Layout => {
+                // Stacks with crates to take away, and stacks with crates to
+                // put on, above what they already share with the target.
+                let (mut lifts, mut drops) = (0, 0);
+                for (stack, target) in pairs {
+                    let common = stack.iter().zip(target).take_while(|(a, b)| a == b).count();
+                    lifts += usize::from(stack.len() > common);
+                    drops += usize::from(target.len() > common);
+                }
+                lifts.max(drops)
+            }
+        }
+    }
+}
+
+impl Display for Goal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Goal::Tops => write!(f, "same tops"),
+            Goal::Layout => write!(f, "same layout"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// A shortest procedure reaching the goal, shorter than the original.
+    Shortest(Vec<Move>),
+    /// A procedure reaching the goal, shorter than the original but maybe
+    /// not the shortest, found by shortening parts of the original.
+    Shorter(Vec<Move>),
+    /// No procedure reaching the goal is shorter than the original.
+    Optimal,
+    /// The search expanded as many states as it was allowed to, and no part
+    /// of the original could be shortened.
+    GaveUp,
+}
+
+#[derive(Debug)]
+pub struct Optimization {
+    pub crane: &'static str,
+    pub goal: Goal,
+    /// Number of moves of the original procedure.
+    pub original: usize,
+    /// Number of states expanded by every search.
+    pub expanded: usize,
+    pub outcome: Outcome,
+}
+
+impl Optimization {
+    /// Moves saved by the shorter procedure, if one was found.
+    pub fn saved(&self) -> usize {
+        match &self.outcome {
+            Outcome::Shortest(procedure) | Outcome::Shorter(procedure) => {
+                self.original - procedure.len()
+            }
+            Outcome::Optimal | Outcome::GaveUp => 0,
+        }
+    }
+}
+
+impl Display for Optimization {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}, {}: {} moves, ",
+            self.crane, self.goal, self.original
+        )?;
+        match &self.outcome {
+            Outcome::Shortest(procedure) => {
+                write!(f, "{} suffice ({} saved)", procedure.len(), self.saved())
+            }
+            Outcome::Shorter(procedure) => write!(
+                f,
+                "{} suffice ({} saved), maybe fewer",
+                procedure.len(),
+                self.saved()
+            ),
+            Outcome::Optimal => write!(f, "already the shortest"),
+            Outcome::GaveUp => write!(f, "none shorter found in {} states", self.expanded),
+        }
+    }
+}
+
+/// Every move `stacks` allow, each taking at least one crate.
+fn moves(stacks: &Stacks) -> impl Iterator<Item = Move> + '_ {
+    let numbers = 1..=stacks.len() as u32;
+    numbers.clone().flat_map(move |from| {
+        let crates = stacks.get(from).map_or(0, <[_]>::len) as u32;
+        numbers
+            .clone()
+            .filter(move |&to| to != from)
+            .flat_map(move |to| (1..=crates).map(move |quantity| (quantity, from, to)))
+    })
+}
+
+/// Searches for a procedure shorter than `bound` moves that `crane` can carry
+/// out on `stacks` to reach `goal` for `target`, expanding at most `limit`
+/// states. Returns the outcome and the number of states expanded.
+///
+/// This is an A* search by the moves made plus a lower bound of the moves
+/// left, so the first procedure found is a shortest one. States whose bound
+/// reaches `bound` are never explored, so running out of states proves no
+/// shorter procedure exists.
+fn search(
+    crane: &impl Crane,
+    stacks: &Stacks,
+    target: &Stacks,
+    goal: Goal,
+    bound: usize,
+    limit: usize,
+) -> (Outcome, usize) {
+    // Every state reached, with the move reaching it from its parent.
+    let mut states: Vec<(Stacks, Option<(usize, Move)>)> = Vec::new();
+    let mut fewest_moves: HashMap<Stacks, usize> = HashMap::new();
+    // Candidates by estimated total moves, then deepest first.
+    let mut open = BinaryHeap::new();
+
+    let estimate = goal.estimate(stacks, target);
+    if estimate < bound {
+        states.push((stacks.clone(), None));
+        fewest_moves.insert(stacks.clone(), 0);
+        open.push(Reverse((estimate, Reverse(0), 0)));
+    }
+
+    let mut expanded = 0;
+    let outcome = loop {
+        let Some(Reverse((estimate, Reverse(depth), id))) = open.pop() else {
+            break Outcome::Optimal;
+        };
+        if fewest_moves[&states[id].0] < depth {
+            continue;
+        }
+        if estimate == depth {
+            break Outcome::Shortest(path(&states, id));
+        }
+        if expanded == limit {
+            break Outcome::GaveUp;
+        }
+        expanded += 1;
+
+        let successors: Vec<_> = moves_from(crane, &states[id].0).collect();
+        for (step, next) in successors {
+            let next_depth = depth + 1;
+            let estimate = next_depth + goal.estimate(&next, target);
+            if estimate >= bound
+                || fewest_moves
+                    .get(&next)
+                    .is_some_and(|&fewest| fewest <= next_depth)
+            {
+                continue;
+            }
+
+            fewest_moves.insert(next.clone(), next_depth);
+            states.push((next, Some((id, step))));
+            open.push(Reverse((estimate, Reverse(next_depth), states.len() - 1)));
+        }
+    };
+
+    (outcome, expanded)
+}
+
+/// Moves of the original procedure replaced at once when shortening it part
+/// by part.
+const WINDOW: usize = 3;
+
+/// Shortens `procedure` by replacing every run of [`WINDOW`] moves with a
+/// shortest run leaving the same layout, expanding at most `limit` states
+/// per run. Returns the shortened procedure and the number of states
+/// expanded.
+fn shorten_windows(
+    crane: &impl Crane,
+    stacks: &Stacks,
+    procedure: &[Move],
+    limit: usize,
+) -> Result<(Vec<Move>, usize), Error> {
+    let mut shortened = Vec::new();
+    let mut rest = procedure.to_vec();
+    let mut stacks = stacks.clone();
+    let mut expanded = 0;
+
+    while !rest.is_empty() {
+        let window = WINDOW.min(rest.len());
+        let mut target = stacks.clone();
+        crane.run(&mut target, &rest[..window])?;
+
+        let (outcome, window_expanded) =
+            search(crane, &stacks, &target, Goal::Layout, window, limit);
+        expanded += window_expanded;
+
+        if let Outcome::Shortest(replacement) = outcome {
+            // The next window starts with the replacement, which may combine
+            // with the moves after it.
+            rest.splice(..window, replacement);
+        } else {
+            let step = rest.remove(0);
+            crane.apply(&mut stacks, step)?;
+            shortened.push(step);
+        }
+    }
+
+    Ok((shortened, expanded))
+}
+
+/// Searches for a shorter procedure `crane` can carry out on `stacks` to
+/// reach `goal` as `procedure` does.
+///
+/// An A* search looks for a shortest procedure first. If it expands `limit`
+/// states without an answer, every run of a few moves of the original is
+/// replaced by a shortest run with the same effect instead, which keeps the
+/// whole layout.
+pub fn optimize(
+    crane: &impl Crane,
+    stacks: &Stacks,
+    procedure: &[Move],
+    goal: Goal,
+    limit: usize,
+) -> Result<Optimization, Error> {
+    let mut target = stacks.clone();
+    crane.run(&mut target, procedure)?;
+
+    let (mut outcome, mut expanded) = search(crane, stacks, &target, goal, procedure.len(), limit);
+    if outcome == Outcome::GaveUp {
+        let (shortened, windows_expanded) = shorten_windows(crane, stacks, procedure, limit)?;
+        expanded += windows_expanded;
+        if shortened.len() < procedure.len() {
+            outcome = Outcome::Shorter(shortened);
+        }
+    }
+
+    Ok(Optimization {
+        crane: crane.name(),
+        goal,
+        original: procedure.len(),
+        expanded,
+        outcome,
+    })
+}
+
+/// Every move `crane` can make on `stacks`, with the stacks it leaves.
+fn moves_from<'a>(
+    crane: &'a impl Crane,
+    stacks: &'a Stacks,
+) -> impl Iterator<Item = (Move, Stacks)> + 'a {
+    moves(stacks).filter_map(move |step| {
+        let mut next = stacks.clone();
+        crane.apply(&mut next, step).ok()?;
+        Some((step, next))
+    })
+}
+
+/// The moves leading to state `id`.
+fn path(states: &[(Stacks, Option<(usize, Move)>)], mut id: usize) -> Vec<Move> {
+    let mut procedure = Vec::new();
+    while let Some((parent, step)) = states[id].1 {
+        procedure.push(step);
+        id = parent;
+    }
+    procedure.reverse();
+    procedure
+}
+
+#[cfg(test)]
+mod test {
+    use itertools::Itertools;
+    use proptest::prelude::*;
+
+    use super::{Goal, Outcome};
+    use crate::day_five::{Crane, CrateMover9000, CrateMover9001, Stacks};
+
+    fn example() -> Stacks {
+        Stacks::from(vec![vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']])
+    }
+
+    const PROCEDURE: [(u32, u32, u32); 4] = [(1, 2, 1), (3, 1, 3), (2, 2, 1), (1, 1, 2)];
+
+    #[test]
+    fn undone_moves() {
+        let stacks = Stacks::from(vec![vec!['A'], vec!['B']]);
+        let procedure = [(1, 1, 2), (1, 2, 1)];
+
+        let optimization =
+            super::optimize(&CrateMover9000, &stacks, &procedure, Goal::Layout, 100).unwrap();
+        assert_eq!(optimization.outcome, Outcome::Shortest(vec![]));
+        assert_eq!(optimization.saved(), 2);
+        assert_eq!(
+            optimization.to_string(),
+            "CrateMover 9000, same layout: 2 moves, 0 suffice (2 saved)"
+        );
+    }
+
+    #[test]
+    fn example_procedure() {
+        let optimization =
+            super::optimize(&CrateMover9001, &example(), &PROCEDURE, Goal::Tops, 10_000).unwrap();
+        let Outcome::Shortest(procedure) = &optimization.outcome else {
+            panic!("{optimization}");
+        };
+
+        let (mut expected, mut stacks) = (example(), example());
+        CrateMover9001.run(&mut expected, &PROCEDURE).unwrap();
+        CrateMover9001.run(&mut stacks, procedure).unwrap();
+        assert_eq!(stacks.tops(), "MCD");
+        assert_eq!(stacks.tops(), expected.tops());
+        assert_eq!(optimization.saved(), 1);
+
+        let optimization = super::optimize(
+            &CrateMover9000,
+            &example(),
+            &PROCEDURE,
+            Goal::Layout,
+            10_000,
+        )
+        .unwrap();
+        assert_eq!(
+            optimization.to_string(),
+            "CrateMover 9000, same layout: 4 moves, 3 suffice (1 saved)"
+        );
+
+        let stacks = Stacks::from(vec![vec!['A'], vec![]]);
+        let optimization =
+            super::optimize(&CrateMover9000, &stacks, &[(1, 1, 2)], Goal::Layout, 100).unwrap();
+        assert_eq!(
+            optimization.to_string(),
+            "CrateMover 9000, same layout: 1 moves, already the shortest"
+        );
+    }
+
+    #[test]
+    fn gives_up() {
+        let optimization =
+            super::optimize(&CrateMover9000, &example(), &PROCEDURE, Goal::Layout, 0).unwrap();
+        assert_eq!(optimization.outcome, Outcome::GaveUp);
+        assert_eq!(
+            optimization.to_string(),
+            "CrateMover 9000, same layout: 4 moves, none shorter found in 0 states"
+        );
+    }
+
+    #[test]
+    fn shortens_windows() {
+        let stacks = Stacks::from(vec![vec!['A', 'B', 'C'], vec![], vec![]]);
+        let procedure = [(1, 1, 2), (1, 1, 2), (1, 1, 2), (1, 2, 3)];
+
+        let optimization =
+            super::optimize(&CrateMover9000, &stacks, &procedure, Goal::Layout, 1).unwrap();
+        assert_eq!(
+            optimization.outcome,
+            Outcome::Shorter(vec![(3, 1, 2), (1, 2, 3)])
+        );
+        assert_eq!(
+            optimization.to_string(),
+            "CrateMover 9000, same layout: 4 moves, 2 suffice (2 saved), maybe fewer"
+        );
+    }
+
+    #[test]
+    fn invalid_procedure() {
+        assert!(
+            super::optimize(&CrateMover9000, &example(), &[(4, 1, 2)], Goal::Tops, 10).is_err()
+        );
+    }
+
+    fn check(
+        crane: impl Crane,
+        stacks: &Stacks,
+        procedure: &[(u32, u32, u32)],
+        goal: Goal,
+        limit: usize,
+    ) -> Result<(), TestCaseError> {
+        let mut expected = stacks.clone();
+        if crane.run(&mut expected, procedure).is_err() {
+            return Ok(());
+        }
+
+        let optimization = super::optimize(&crane, stacks, procedure, goal, limit).unwrap();
+        if let Outcome::Shortest(shorter) | Outcome::Shorter(shorter) = &optimization.outcome {
+            let mut reached = stacks.clone();
+            crane.run(&mut reached, shorter).unwrap();
+
+            prop_assert!(shorter.len() < procedure.len());
+            match goal {
+                Goal::Tops => prop_assert_eq!(reached.tops(), expected.tops()),
+                Goal::Layout => prop_assert_eq!(reached, expected),
+            }
+        }
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn shorter_procedures_reach_the_goal(
+            stacks in prop::collection::vec(prop::collection::vec(prop::char::range('A', 'C'), 0..3), 3),
+            procedure in prop::collection::vec((1..3u32, 1..4u32, 1..4u32), 0..6),
+        ) {
+            let stacks = Stacks::from(stacks);
+            // A limit of 1 makes the exact search give up, so the windows
+            // are searched instead.
+            for (goal, limit) in [Goal::Tops, Goal::Layout].into_iter().cartesian_product([1, 10_000]) {
+                check(CrateMover9000, &stacks, &procedure, goal, limit)?;
+                check(CrateMover9001, &stacks, &procedure, goal, limit)?;
+            }
+        }
+    }
+}
@@ -0,0 +1,260 @@
+//! Replays a procedure one move at a time, in either direction.
+
+use std::fmt::{self, Display};
+
+use super::{parse, Crane, Label, Move, Stacks};
+use crate::{error::parse_all, Error};
+
+/// A procedure being carried out by a crane, which can be stepped through
+/// forward and back.
+#[derive(Clone, Debug)]
+pub struct Replay<C> {
+    crane: C,
+    procedure: Vec<Move>,
+    stacks: Stacks,
+    /// Crates lifted by each move made so far, from the bottom up, as they
+    /// were on the stack they were moved from.
+    lifted: Vec<Vec<Label>>,
+}
+
+impl<C: Crane> Replay<C> {
+    pub fn new(crane: C, stacks: Stacks, procedure: Vec<Move>) -> Self {
+        Self {
+            crane,
+            procedure,
+            stacks,
+            lifted: Vec::new(),
+        }
+    }
+
+    /// Replays the procedure of `input`, starting from its drawing.
+    pub fn from_input(crane: C, input: &str) -> Result<Self, Error> {
+        let (stacks, procedure) = parse_all(input, parse)?;
+        Ok(Self::new(crane, stacks, procedure))
+    }
+
+    /// The stacks after the moves made so far.
+    pub fn stacks(&self) -> &Stacks {
+        &self.stacks
+    }
+
+    /// Number of moves made so far.
+    pub fn position(&self) -> usize {
+        self.lifted.len()
+    }
+
+    /// Number of moves in the procedure.
+    pub fn len(&self) -> usize {
+        self.procedure.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.procedure.is_empty()
+    }
+
+    /// The move made last, if any.
+    pub fn last_move(&self) -> Option<Move> {
+        self.position()
+            .checked_sub(1)
+            .map(|index| self.procedure[index])
+    }
+
+    /// Makes the next move, returning whether there was one. The replay
+    /// stays where it is if the move fails.
+    pub fn step_forward(&mut self) -> Result<bool, Error> {
+        let Some(&step) = self.procedure.get(self.position()) else {
+            return Ok(false);
+        };
+
+        let (quantity, from, _) = step;
+        let stack = self.stacks.get(from).unwrap_or_default();
+        let lifted = stack
+            .len()
+            .checked_sub(quantity as usize)
+            .map(|split| stack[split..].to_vec())
+            .unwrap_or_default();
+
+        let number = self.position() + 1;
+        self.crane
+            .apply(&mut self.stacks, step)
+            .map_err(|error| match error {
+                Error::Invalid(message) => Error::Invalid(format!("move {number}: {message}")),
+                error => error,
+            })?;
+        self.lifted.push(lifted);
+        Ok(true)
+    }
+
+    /// Undoes the last move, returning whether there was one.
+    pub fn step_back(&mut self) -> bool {
+        let Some((quantity, from, to)) = self.last_move() else {
+            return false;
+        };
+        let lifted = self.lifted.pop().unwrap();
+
+        // The move succeeded, so both stacks exist and `to` holds the moved
+        // crates on top, in whatever order the crane left them.
+        let to = self.stacks.stack_mut(to).unwrap();
+        to.truncate(to.len() - quantity as usize);
+        self.stacks.stack_mut(from).unwrap().extend(lifted);
+        true
+    }
+
+    /// Steps forward or back until `position` moves have been made.
+    pub fn jump_to(&mut self, position: usize) -> Result<(), Error> {
+        if position > self.len() {
+            return Err(Error::Invalid(format!(
+                "procedure has only {} moves",
+                self.len()
+            )));
+        }
+
+        while self.position() > position {
+            self.step_back();
+        }
+        while self.position() < position {
+            self.step_forward()?;
+        }
+        Ok(())
+    }
+}
+
+/// A frame of the replay: the last move made, then the stacks it left.
+impl<C> Display for Replay<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let position = self.lifted.len();
+        match position.checked_sub(1).map(|index| self.procedure[index]) {
+            Some((quantity, from, to)) => writeln!(
+                f,
+                "Move {position}/{}: move {quantity} from {from} to {to}",
+                self.procedure.len()
+            )?,
+            None => writeln!(f, "Move 0/{}: start", self.procedure.len())?,
+        }
+
+        write!(f, "{}", self.stacks)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+    use proptest::prelude::*;
+
+    use super::Replay;
+    use crate::{
+        day_five::{Crane, CrateMover9000, CrateMover9001, Stacks},
+        Error,
+    };
+
+    const INPUT: &str = indoc! {"
+            [D]    
+        [N] [C]    
+        [Z] [M] [P]
+         1   2   3 
+
+        move 1 from 2 to 1
+        move 3 from 1 to 3
+        move 2 from 2 to 1
+        move 1 from 1 to 2
+    "};
+
+    #[test]
+    fn steps() {
+        let mut replay = Replay::from_input(CrateMover9001, INPUT).unwrap();
+        let start = replay.stacks().clone();
+
+        assert!(replay.step_forward().unwrap());
+        assert!(replay.step_forward().unwrap());
+        assert_eq!(replay.stacks().tops(), " CD");
+        assert_eq!(replay.last_move(), Some((3, 1, 3)));
+
+        assert!(replay.step_back());
+        assert_eq!(replay.stacks().tops(), "DCP");
+        assert!(replay.step_back());
+        assert!(!replay.step_back());
+        assert_eq!(replay.stacks(), &start);
+
+        replay.jump_to(4).unwrap();
+        assert!(!replay.step_forward().unwrap());
+        assert_eq!(replay.stacks().tops(), "MCD");
+        assert_eq!(
+            replay.jump_to(5),
+            Err(Error::Invalid("procedure has only 4 moves".to_string()))
+        );
+    }
+
+    #[test]
+    fn frames() {
+        let mut replay = Replay::from_input(CrateMover9000, INPUT).unwrap();
+        assert_eq!(
+            replay.to_string(),
+            indoc! {"
+                Move 0/4: start
+                    [D]    
+                [N] [C]    
+                [Z] [M] [P]
+                 1   2   3 
+            "}
+        );
+
+        replay.jump_to(1).unwrap();
+        assert_eq!(
+            replay.to_string(),
+            indoc! {"
+                Move 1/4: move 1 from 2 to 1
+                [D]        
+                [N] [C]    
+                [Z] [M] [P]
+                 1   2   3 
+            "}
+        );
+    }
+
+    #[test]
+    fn failed_step() {
+        let stacks = Stacks::from(vec![vec!['A'], vec![]]);
+        let mut replay = Replay::new(CrateMover9000, stacks, vec![(1, 1, 2), (1, 1, 2)]);
+
+        assert_eq!(
+            replay.jump_to(2),
+            Err(Error::Invalid(
+                "move 2: stack 1 has too few crates to move".to_string()
+            ))
+        );
+        assert_eq!(replay.position(), 1);
+        assert_eq!(replay.stacks().tops(), " A");
+    }
+
+    fn replay(
+        crane: impl Crane,
+        stacks: Vec<Vec<char>>,
+        procedure: Vec<(u32, u32, u32)>,
+    ) -> Result<(), TestCaseError> {
+        let stacks = Stacks::from(stacks);
+        let mut replay = Replay::new(crane, stacks.clone(), procedure);
+
+        let mut snapshots = vec![stacks];
+        while let Ok(true) = replay.step_forward() {
+            snapshots.push(replay.stacks().clone());
+        }
+
+        while let Some(snapshot) = snapshots.pop() {
+            prop_assert_eq!(replay.stacks(), &snapshot);
+            replay.step_back();
+        }
+        prop_assert_eq!(replay.position(), 0);
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn stepping_back_undoes_moves(
+            stacks in prop::collection::vec(prop::collection::vec(prop::char::range('A', 'E'), 0..6), 1..5),
+            procedure in prop::collection::vec((0..4u32, 0..6u32, 0..6u32), 0..20),
+        ) {
+            replay(CrateMover9000, stacks.clone(), procedure.clone())?;
+            replay(CrateMover9001, stacks, procedure)?;
+        }
+    }
+}
@@ -1,35 +1,204 @@
-use std::ops::RangeInclusive;
+use std::{
+    cmp::Ordering,
+    collections::BTreeMap,
+    fmt::{self, Display},
+    ops::RangeInclusive,
+};
 
 use nom::{
     character::complete::{char, newline, u32 as parse_u32},
-    combinator::map,
+    combinator::{map, verify},
     multi::separated_list1,
     sequence::separated_pair,
     IResult,
 };
 
-type Assignment = RangeInclusive<u32>;
-type Pair = (Assignment, Assignment);
+use crate::{error::parse_all, Error};
+
+pub mod index;
 
+pub type Assignment = RangeInclusive<u32>;
+pub type Pair = (Assignment, Assignment);
+
+/// Parses an assignment, rejecting ranges that end before they start.
 fn parse_assignment(input: &str) -> IResult<&str, Assignment> {
-    map(separated_pair(parse_u32, char('-'), parse_u32), |(a, b)| {
-        RangeInclusive::new(a, b)
-    })(input)
+    map(
+        verify(
+            separated_pair(parse_u32, char('-'), parse_u32),
+            |(a, b)| a <= b,
+        ),
+        |(a, b)| RangeInclusive::new(a, b),
+    )(input)
 }
 
-fn parse(input: &str) -> IResult<&str, Vec<Pair>> {
+pub fn parse(input: &str) -> IResult<&str, Vec<Pair>> {
     separated_list1(
         newline,
         separated_pair(parse_assignment, char(','), parse_assignment),
     )(input)
 }
 
+/// How two assignments are placed relative to each other, after Allen's
+/// interval algebra. Sections are discrete, so an assignment meets the next
+/// one when it ends on the section just before it starts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Relation {
+    Before,
+    Meets,
+    Overlaps,
+    Starts,
+    During,
+    Finishes,
+    Equals,
+    FinishedBy,
+    Contains,
+    StartedBy,
+    OverlappedBy,
+    MetBy,
+    After,
+}
+
+impl Relation {
+    pub const ALL: [Self; 13] = [
+        Self::Before,
+        Self::Meets,
+        Self::Overlaps,
+        Self::Starts,
+        Self::During,
+        Self::Finishes,
+        Self::Equals,
+        Self::FinishedBy,
+        Self::Contains,
+        Self::StartedBy,
+        Self::OverlappedBy,
+        Self::MetBy,
+        Self::After,
+    ];
+
+    /// The relation of `b` to `a` when `a` has this relation to `b`.
+    pub fn inverse(self) -> Self {
+        match self {
+            Self::Before => Self::After,
+            Self::Meets => Self::MetBy,
+            Self::Overlaps => Self::OverlappedBy,
+            Self::Starts => Self::StartedBy,
+            Self::During => Self::Contains,
+            Self::Finishes => Self::FinishedBy,
+            Self::Equals => Self::Equals,
+            Self::FinishedBy => Self::Finishes,
+            Self::Contains => Self::During,
+            Self::StartedBy => Self::Starts,
+            Self::OverlappedBy => Self::Overlaps,
+            Self::MetBy => Self::Meets,
+            Self::After => Self::Before,
+        }
+    }
+
+    /// Whether one of the assignments contains the other.
+    pub fn is_containment(self) -> bool {
+        matches!(
+            self,
+            Self::Starts
+                | Self::During
+                | Self::Finishes
+                | Self::Equals
+                | Self::FinishedBy
+                | Self::Contains
+                | Self::StartedBy
+        )
+    }
+
+    /// Whether the assignments share at least one section.
+    pub fn is_overlap(self) -> bool {
+        !matches!(self, Self::Before | Self::Meets | Self::MetBy | Self::After)
+    }
+}
+
+impl Display for Relation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Before => "before",
+            Self::Meets => "meets",
+            Self::Overlaps => "overlaps",
+            Self::Starts => "starts",
+            Self::During => "during",
+            Self::Finishes => "finishes",
+            Self::Equals => "equals",
+            Self::FinishedBy => "finished by",
+            Self::Contains => "contains",
+            Self::StartedBy => "started by",
+            Self::OverlappedBy => "overlapped by",
+            Self::MetBy => "met by",
+            Self::After => "after",
+        };
+        f.pad(name)
+    }
+}
+
+/// The relation of `a` to `b`. Both assignments are expected to contain at
+/// least one section.
+pub fn relation(a: &Assignment, b: &Assignment) -> Relation {
+    match (a.start().cmp(b.start()), a.end().cmp(b.end())) {
+        (Ordering::Equal, Ordering::Equal) => Relation::Equals,
+        (Ordering::Equal, Ordering::Less) => Relation::Starts,
+        (Ordering::Equal, Ordering::Greater) => Relation::StartedBy,
+        (Ordering::Greater, Ordering::Equal) => Relation::Finishes,
+        (Ordering::Less, Ordering::Equal) => Relation::FinishedBy,
+        (Ordering::Greater, Ordering::Less) => Relation::During,
+        (Ordering::Less, Ordering::Greater) => Relation::Contains,
+        (Ordering::Less, Ordering::Less) => {
+            if a.end().checked_add(1) == Some(*b.start()) {
+                Relation::Meets
+            } else if a.end() < b.start() {
+                Relation::Before
+            } else {
+                Relation::Overlaps
+            }
+        }
+        (Ordering::Greater, Ordering::Greater) => relation(b, a).inverse(),
+    }
+}
+
 fn fully_overlaps(a: &Assignment, b: &Assignment) -> bool {
-    a.contains(b.start()) && a.contains(b.end()) || b.contains(a.start()) && b.contains(a.end())
+    relation(a, b).is_containment()
 }
 
 fn partially_overlaps(a: &Assignment, b: &Assignment) -> bool {
-    a.contains(b.start()) || a.contains(b.end()) || b.contains(a.start()) || b.contains(a.end())
+    relation(a, b).is_overlap()
+}
+
+/// Number of pairs in each relation, including the relations no pair is in.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Classification {
+    pub counts: BTreeMap<Relation, usize>,
+}
+
+pub fn classify(pairs: &[Pair]) -> Classification {
+    let mut counts: BTreeMap<_, _> = Relation::ALL
+        .iter()
+        .map(|&relation| (relation, 0))
+        .collect();
+    for (a, b) in pairs {
+        *counts.entry(relation(a, b)).or_default() += 1;
+    }
+
+    Classification { counts }
+}
+
+impl Display for Classification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Relations:")?;
+        for (relation, count) in &self.counts {
+            let parts = match (relation.is_containment(), relation.is_overlap()) {
+                (true, _) => " (parts one and two)",
+                (false, true) => " (part two)",
+                (false, false) => "",
+            };
+            writeln!(f, "  {relation:<13} {count:>5}{parts}")?;
+        }
+
+        Ok(())
+    }
 }
 
 pub struct Solution;
@@ -38,19 +207,19 @@ impl crate::Solution for Solution {
     type O1 = usize;
     type O2 = usize;
 
-    fn part_one(input: &str) -> Self::O1 {
-        let (_, pairs) = parse(input).expect("Failed to parse assignment pairs");
+    fn part_one(input: &str) -> Result<Self::O1, Error> {
+        let pairs = parse_all(input, parse)?;
 
-        pairs.iter().filter(|(a, b)| fully_overlaps(a, b)).count()
+        Ok(pairs.iter().filter(|(a, b)| fully_overlaps(a, b)).count())
     }
 
-    fn part_two(input: &str) -> Self::O2 {
-        let (_, pairs) = parse(input).expect("Failed to parse assignment pairs");
+    fn part_two(input: &str) -> Result<Self::O2, Error> {
+        let pairs = parse_all(input, parse)?;
 
-        pairs
+        Ok(pairs
             .iter()
             .filter(|(a, b)| partially_overlaps(a, b))
-            .count()
+            .count())
     }
 }
 
@@ -59,11 +228,34 @@ mod test {
     use std::ops::RangeInclusive;
 
     use indoc::indoc;
+    use proptest::prelude::*;
+
+    use super::Relation;
+    use crate::{interval_set::IntervalSet, Error, Solution};
+
+    fn assignment() -> impl Strategy<Value = RangeInclusive<u32>> {
+        (0..100u32, 0..100u32).prop_map(|(a, b)| a.min(b)..=a.max(b))
+    }
 
     #[test]
     fn parse_assignment() {
         let (_, assignment) = super::parse_assignment("5-20").unwrap();
         assert_eq!(assignment, 5..=20);
+        let (_, assignment) = super::parse_assignment("7-7").unwrap();
+        assert_eq!(assignment, 7..=7);
+        assert!(super::parse_assignment("5-3").is_err());
+    }
+
+    #[test]
+    fn reversed_assignment() {
+        let input = indoc! {"
+            1-3,2-7
+            5-3,4-22
+        "};
+        let error = Error::Parse("line 2: unexpected input `5-3,4-22`".to_string());
+
+        assert_eq!(super::Solution::part_one(input), Err(error.clone()));
+        assert_eq!(super::Solution::part_two(input), Err(error));
     }
 
     #[test]
@@ -78,6 +270,7 @@ mod test {
     }
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn fully_overlaps() {
         assert_eq!(
             super::fully_overlaps(&RangeInclusive::new(2, 8), &RangeInclusive::new(3, 7)),
@@ -102,6 +295,7 @@ mod test {
     }
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn partially_overlaps() {
         assert_eq!(
             super::partially_overlaps(&RangeInclusive::new(2, 8), &RangeInclusive::new(3, 7)),
@@ -124,4 +318,98 @@ mod test {
             false
         );
     }
+
+    #[test]
+    fn relation() {
+        let relations = [
+            ((1, 2), (4, 5), Relation::Before),
+            ((1, 3), (4, 5), Relation::Meets),
+            ((1, 4), (4, 5), Relation::Overlaps),
+            ((4, 4), (4, 5), Relation::Starts),
+            ((3, 4), (2, 5), Relation::During),
+            ((5, 5), (4, 5), Relation::Finishes),
+            ((4, 5), (4, 5), Relation::Equals),
+            ((3, 5), (4, 5), Relation::FinishedBy),
+            ((1, 9), (4, 5), Relation::Contains),
+            ((4, 7), (4, 5), Relation::StartedBy),
+            ((5, 7), (4, 5), Relation::OverlappedBy),
+            ((6, 7), (4, 5), Relation::MetBy),
+            ((7, 7), (4, 5), Relation::After),
+        ];
+
+        for ((a, b), (c, d), expected) in relations {
+            assert_eq!(super::relation(&(a..=b), &(c..=d)), expected);
+        }
+        assert_eq!(
+            super::relation(&(u32::MAX..=u32::MAX), &(0..=1)),
+            Relation::After
+        );
+    }
+
+    #[test]
+    fn classify() {
+        let input = indoc! {"
+            2-4,6-8
+            2-3,4-5
+            5-7,7-9
+            2-8,3-7
+            6-6,4-6
+            2-6,4-8
+        "};
+        let (_, pairs) = super::parse(input).unwrap();
+
+        assert_eq!(
+            super::classify(&pairs).to_string(),
+            indoc! {"
+                Relations:
+                  before            1
+                  meets             1
+                  overlaps          2 (part two)
+                  starts            0 (parts one and two)
+                  during            0 (parts one and two)
+                  finishes          1 (parts one and two)
+                  equals            0 (parts one and two)
+                  finished by       0 (parts one and two)
+                  contains          1 (parts one and two)
+                  started by        0 (parts one and two)
+                  overlapped by     0 (part two)
+                  met by            0
+                  after             0
+            "}
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn relation_is_inverted_by_swapping(a in assignment(), b in assignment()) {
+            prop_assert_eq!(super::relation(&b, &a), super::relation(&a, &b).inverse());
+        }
+
+        #[test]
+        fn overlaps_match_interval_sets(a in assignment(), b in assignment()) {
+            let (set_a, set_b) = (IntervalSet::from(a.clone()), IntervalSet::from(b.clone()));
+
+            prop_assert_eq!(
+                super::fully_overlaps(&a, &b),
+                set_a.is_superset(&set_b) || set_b.is_superset(&set_a)
+            );
+            prop_assert_eq!(
+                super::partially_overlaps(&a, &b),
+                !set_a.intersection(&set_b).is_empty()
+            );
+        }
+
+        #[test]
+        fn fully_overlaps_implies_partially_overlaps(a in assignment(), b in assignment()) {
+            if super::fully_overlaps(&a, &b) {
+                prop_assert!(super::partially_overlaps(&a, &b));
+            }
+        }
+
+        #[test]
+        fn overlaps_are_symmetric(a in assignment(), b in assignment()) {
+            prop_assert_eq!(super::fully_overlaps(&a, &b), super::fully_overlaps(&b, &a));
+            prop_assert_eq!(super::partially_overlaps(&a, &b), super::partially_overlaps(&b, &a));
+        }
+    }
 }
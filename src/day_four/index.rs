@@ -0,0 +1,329 @@
+//! Queries across the assignments of every pair, rather than within a pair.
+
+use std::fmt::{self, Display};
+
+use super::{Assignment, Pair};
+
+/// An assignment and where it comes from in the input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Assigned {
+    /// Line of the input, starting at 1.
+    pub line: usize,
+    /// Position of the elf in its pair, 0 or 1.
+    pub elf: usize,
+    pub sections: Assignment,
+}
+
+/// An interval tree over every assignment, plus the number of elves assigned
+/// to each section.
+///
+/// The tree is implicit: assignments are sorted by their first section, and
+/// the middle of every slice is the root of the subtree over that slice.
+#[derive(Debug)]
+pub struct SectionIndex {
+    assigned: Vec<Assigned>,
+    /// Greatest last section of the subtree rooted at each assignment.
+    max_end: Vec<u32>,
+    /// Sections at which the depth changes, with the depth from there on.
+    /// Positions are `u64` because depth changes past `u32::MAX`.
+    depths: Vec<(u64, usize)>,
+    /// `sparse[k][i]` is the greatest depth of `depths[i..i + 2^k]`.
+    sparse: Vec<Vec<usize>>,
+}
+
+impl SectionIndex {
+    /// Indexes both assignments of every pair. Reversed assignments such as
+    /// `5-3` contain no section and are left out.
+    pub fn new(pairs: &[Pair]) -> Self {
+        let mut assigned: Vec<Assigned> = pairs
+            .iter()
+            .enumerate()
+            .flat_map(|(index, (a, b))| {
+                [a, b]
+                    .into_iter()
+                    .enumerate()
+                    .map(move |(elf, sections)| Assigned {
+                        line: index + 1,
+                        elf,
+                        sections: sections.clone(),
+                    })
+            })
+            .filter(|assigned| !assigned.sections.is_empty())
+            .collect();
+        assigned.sort_by_key(|assigned| *assigned.sections.start());
+
+        let mut max_end = vec![0; assigned.len()];
+        fill_max_end(&assigned, &mut max_end, 0, assigned.len());
+
+        let depths = sweep(&assigned);
+        let sparse = sparse_table(&depths);
+
+        Self {
+            assigned,
+            max_end,
+            depths,
+            sparse,
+        }
+    }
+
+    /// Number of assignments indexed.
+    pub fn len(&self) -> usize {
+        self.assigned.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.assigned.is_empty()
+    }
+
+    /// Assignments containing `section`, ordered by their first section.
+    pub fn at(&self, section: u32) -> Vec<&Assigned> {
+        self.overlapping(&(section..=section))
+    }
+
+    /// Assignments sharing at least one section with `range`, ordered by
+    /// their first section.
+    pub fn overlapping(&self, range: &Assignment) -> Vec<&Assigned> {
+        let mut found = Vec::new();
+        if !range.is_empty() {
+            self.collect(0, self.assigned.len(), range, &mut found);
+        }
+        found
+    }
+
+    fn collect<'a>(
+        &'a self,
+        low: usize,
+        high: usize,
+        range: &Assignment,
+        found: &mut Vec<&'a Assigned>,
+    ) {
+        if low >= high {
+            return;
+        }
+
+        let middle = (low + high) / 2;
+        if self.max_end[middle] < *range.start() {
+            // Everything below ends before the range.
+            return;
+        }
+
+        self.collect(low, middle, range, found);
+
+        let assigned = &self.assigned[middle];
+        if assigned.sections.start() <= range.end() {
+            if assigned.sections.end() >= range.start() {
+                found.push(assigned);
+            }
+            // Everything on the right starts after this one.
+            self.collect(middle + 1, high, range, found);
+        }
+    }
+
+    /// Number of elves assigned to `section`.
+    pub fn depth(&self, section: u32) -> usize {
+        self.depths[self.depth_index(section)].1
+    }
+
+    /// Greatest number of elves assigned to a single section of `range`.
+    pub fn max_depth_in(&self, range: &Assignment) -> usize {
+        if range.is_empty() {
+            return 0;
+        }
+
+        let first = self.depth_index(*range.start());
+        let last = self.depth_index(*range.end()) + 1;
+        let level = (last - first).ilog2() as usize;
+        self.sparse[level][first].max(self.sparse[level][last - (1 << level)])
+    }
+
+    /// Greatest number of elves assigned to a single section.
+    pub fn max_depth(&self) -> usize {
+        self.max_depth_in(&(0..=u32::MAX))
+    }
+
+    /// The ranges of sections assigned to [`Self::max_depth`] elves, or
+    /// nothing if no elf is assigned anything.
+    pub fn busiest(&self) -> Vec<Assignment> {
+        let max = self.max_depth();
+        if max == 0 {
+            return Vec::new();
+        }
+
+        self.depths
+            .windows(2)
+            .filter(|pair| pair[0].1 == max)
+            // The depth only changes within `0..=u32::MAX + 1`, and the
+            // last change is always back to 0.
+            .map(|pair| pair[0].0 as u32..=(pair[1].0 - 1) as u32)
+            .collect()
+    }
+
+    /// Index in `depths` of the depth at `section`.
+    fn depth_index(&self, section: u32) -> usize {
+        // `depths` always starts at section 0.
+        self.depths
+            .partition_point(|&(start, _)| start <= u64::from(section))
+            - 1
+    }
+}
+
+fn fill_max_end(assigned: &[Assigned], max_end: &mut [u32], low: usize, high: usize) -> u32 {
+    if low >= high {
+        return 0;
+    }
+
+    let middle = (low + high) / 2;
+    let left = fill_max_end(assigned, max_end, low, middle);
+    let right = fill_max_end(assigned, max_end, middle + 1, high);
+    let end = (*assigned[middle].sections.end()).max(left).max(right);
+    max_end[middle] = end;
+    end
+}
+
+/// The depth profile of the assignments, starting at section 0.
+fn sweep(assigned: &[Assigned]) -> Vec<(u64, usize)> {
+    let mut events: Vec<(u64, isize)> = assigned
+        .iter()
+        .flat_map(|assigned| {
+            [
+                (u64::from(*assigned.sections.start()), 1),
+                (u64::from(*assigned.sections.end()) + 1, -1),
+            ]
+        })
+        .collect();
+    events.sort_unstable();
+
+    let mut depths = vec![(0, 0)];
+    let mut depth: usize = 0;
+    for (position, change) in events {
+        depth = depth.checked_add_signed(change).unwrap();
+        match depths.last_mut() {
+            Some(last) if last.0 == position => last.1 = depth,
+            _ => depths.push((position, depth)),
+        }
+    }
+
+    depths.dedup_by_key(|&mut (_, depth)| depth);
+    depths
+}
+
+fn sparse_table(depths: &[(u64, usize)]) -> Vec<Vec<usize>> {
+    let mut sparse = vec![depths.iter().map(|&(_, depth)| depth).collect::<Vec<_>>()];
+
+    let mut width = 1;
+    while width * 2 <= depths.len() {
+        let previous = sparse.last().unwrap();
+        let level = (0..=depths.len() - width * 2)
+            .map(|i| previous[i].max(previous[i + width]))
+            .collect();
+        sparse.push(level);
+        width *= 2;
+    }
+
+    sparse
+}
+
+impl Display for SectionIndex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        /// Assignments listed for each of the busiest ranges.
+        const LISTED: usize = 5;
+
+        writeln!(f, "Assignments: {}", self.len())?;
+        writeln!(f, "Max depth: {} elves", self.max_depth())?;
+
+        for range in self.busiest() {
+            let assigned = self.overlapping(&range);
+            let mut lines = assigned
+                .iter()
+                .take(LISTED)
+                .map(|assigned| {
+                    let side = if assigned.elf == 0 { "left" } else { "right" };
+                    format!("line {} {side}", assigned.line)
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            if assigned.len() > LISTED {
+                lines += &format!(" and {} more", assigned.len() - LISTED);
+            }
+            writeln!(f, "  sections {}-{}: {lines}", range.start(), range.end())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::ops::RangeInclusive;
+
+    use indoc::indoc;
+    use proptest::prelude::*;
+
+    use super::SectionIndex;
+
+    fn pairs() -> impl Strategy<Value = Vec<(RangeInclusive<u32>, RangeInclusive<u32>)>> {
+        let assignment = (0..50u32, 0..50u32).prop_map(|(a, b)| a..=b);
+        prop::collection::vec((assignment.clone(), assignment), 0..20)
+    }
+
+    #[test]
+    fn queries() {
+        let index = SectionIndex::new(&[(2..=4, 6..=8), (2..=3, 4..=5), (5..=7, 7..=9)]);
+        let lines = |found: Vec<&super::Assigned>| {
+            found
+                .iter()
+                .map(|assigned| (assigned.line, assigned.elf))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(lines(index.at(4)), vec![(1, 0), (2, 1)]);
+        assert_eq!(lines(index.at(1)), vec![]);
+        assert_eq!(lines(index.overlapping(&(8..=20))), vec![(1, 1), (3, 1)]);
+        assert_eq!(index.depth(7), 3);
+        assert_eq!(index.depth(10), 0);
+        assert_eq!(index.max_depth_in(&(2..=5)), 2);
+        assert_eq!(index.max_depth(), 3);
+        assert_eq!(index.busiest(), vec![7..=7]);
+        assert_eq!(
+            index.to_string(),
+            indoc! {"
+                Assignments: 6
+                Max depth: 3 elves
+                  sections 7-7: line 3 left, line 1 right, line 3 right
+            "}
+        );
+    }
+
+    #[test]
+    fn extreme_sections() {
+        let index = SectionIndex::new(&[(0..=u32::MAX, u32::MAX..=u32::MAX)]);
+
+        assert_eq!(index.depth(0), 1);
+        assert_eq!(index.depth(u32::MAX), 2);
+        assert_eq!(index.busiest(), vec![u32::MAX..=u32::MAX]);
+        assert_eq!(SectionIndex::new(&[]).max_depth(), 0);
+    }
+
+    proptest! {
+        #[test]
+        fn matches_brute_force(pairs in pairs(), a in 0..60u32, b in 0..60u32) {
+            let index = SectionIndex::new(&pairs);
+            let all: Vec<_> = pairs.iter().flat_map(|(a, b)| [a, b]).collect();
+            let depth = |section| all.iter().filter(|sections| sections.contains(&section)).count();
+
+            let range = a..=b;
+            prop_assert_eq!(
+                index.overlapping(&range).len(),
+                all.iter()
+                    .filter(|sections| range.clone().any(|section| sections.contains(&section)))
+                    .count()
+            );
+            prop_assert_eq!(index.depth(a), depth(a));
+            prop_assert_eq!(
+                index.max_depth_in(&range),
+                range.clone().map(depth).max().unwrap_or(0)
+            );
+            prop_assert_eq!(index.max_depth(), (0..60).map(depth).max().unwrap());
+        }
+    }
+}
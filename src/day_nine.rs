@@ -10,15 +10,17 @@ use std::{
     iter::{once, repeat},
 };
 
-#[derive(Clone, Copy)]
-enum Direction {
+use crate::{error::complete, Error};
+
+#[derive(Clone, Copy, Debug)]
+pub enum Direction {
     Up,
     Down,
     Left,
     Right,
 }
 
-fn parse_instruction(input: &str) -> IResult<&str, (Direction, usize)> {
+pub fn parse_instruction(input: &str) -> IResult<&str, (Direction, usize)> {
     let direction = alt((
         value(Direction::Up, char('U')),
         value(Direction::Down, char('D')),
@@ -78,15 +80,20 @@ impl crate::Solution for Solution {
     type O1 = usize;
     type O2 = usize;
 
-    fn part_one(input: &str) -> Self::O1 {
+    #[allow(clippy::manual_repeat_n)]
+    fn part_one(input: &str) -> Result<Self::O1, Error> {
         let mut instructions = iterator(input, parse_instruction);
         let directions = instructions.flat_map(|(dir, count)| repeat(dir).take(count));
-        follow_leader(head_positions(directions))
+        let visited = follow_leader(head_positions(directions))
             .collect::<HashSet<_>>()
-            .len()
+            .len();
+
+        complete(input, instructions.finish())?;
+        Ok(visited)
     }
 
-    fn part_two(input: &str) -> Self::O2 {
+    #[allow(clippy::manual_repeat_n)]
+    fn part_two(input: &str) -> Result<Self::O2, Error> {
         let mut instructions = iterator(input, parse_instruction);
         let directions = instructions.flat_map(|(dir, count)| repeat(dir).take(count));
 
@@ -101,16 +108,30 @@ impl crate::Solution for Solution {
         let eight = follow_leader(seven);
         let tail = follow_leader(eight);
 
-        tail.collect::<HashSet<_>>().len()
+        let visited = tail.collect::<HashSet<_>>().len();
+
+        complete(input, instructions.finish())?;
+        Ok(visited)
     }
 }
 
 #[cfg(test)]
 mod test {
     use indoc::indoc;
+    use proptest::prelude::*;
 
+    use super::Direction;
     use crate::Solution;
 
+    fn direction() -> impl Strategy<Value = Direction> {
+        prop_oneof![
+            Just(Direction::Up),
+            Just(Direction::Down),
+            Just(Direction::Left),
+            Just(Direction::Right),
+        ]
+    }
+
     #[test]
     fn part_one() {
         let input = indoc! {"
@@ -123,7 +144,7 @@ mod test {
             L 5
             R 2
         "};
-        assert_eq!(super::Solution::part_one(input), 13);
+        assert_eq!(super::Solution::part_one(input), Ok(13));
     }
 
     #[test]
@@ -138,6 +159,28 @@ mod test {
             L 25
             U 20
         "};
-        assert_eq!(super::Solution::part_two(input), 36);
+        assert_eq!(super::Solution::part_two(input), Ok(36));
+    }
+
+    proptest! {
+        #[test]
+        fn tail_stays_adjacent_to_leader(
+            directions in prop::collection::vec(direction(), 0..200),
+            knots in 1..10usize,
+        ) {
+            let mut leader: Vec<_> = super::head_positions(directions.into_iter()).collect();
+
+            for _ in 0..knots {
+                let tail: Vec<_> = super::follow_leader(leader.iter().copied()).collect();
+                prop_assert_eq!(tail.len(), leader.len());
+
+                for (head, tail) in leader.iter().zip(&tail) {
+                    prop_assert!((head.0 - tail.0).abs() <= 1, "{:?} {:?}", head, tail);
+                    prop_assert!((head.1 - tail.1).abs() <= 1, "{:?} {:?}", head, tail);
+                }
+
+                leader = tail;
+            }
+        }
     }
 }
@@ -1,53 +1,260 @@
-use crate::limit_heap::LimitHeap;
+use std::{collections::BTreeMap, fmt::Display};
+
+use crate::{error::parse_all, input::normalize, limit_heap::LimitHeap, Error};
 use nom::{
-    character::complete::{newline, u32 as parse_u32},
-    combinator::map,
-    multi::{count, separated_list1},
-    IResult,
+    branch::alt,
+    character::complete::{line_ending, newline, u32 as parse_u32},
+    combinator::{consumed, eof},
+    multi::{many0, separated_list1},
+    sequence::terminated,
+    IResult, Offset,
 };
 
 #[derive(Debug, PartialEq, Eq)]
-struct Elf {
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Elf {
+    /// Position among the elves, starting at 0.
+    index: usize,
+    /// Line of the input the elf starts on.
+    line: usize,
     items: Vec<u32>,
 }
 
 impl Elf {
-    fn calories(&self) -> u32 {
-        self.items.iter().sum()
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn items(&self) -> &[u32] {
+        &self.items
+    }
+
+    pub fn calories(&self) -> u64 {
+        self.items.iter().copied().map(u64::from).sum()
+    }
+}
+
+/// Parses elves separated by blank lines. Every additional blank line between
+/// two elves is an elf carrying nothing, while blank lines at the end of the
+/// input are not elves.
+pub fn parse_input(input: &str) -> IResult<&str, Vec<Elf>> {
+    let item = terminated(parse_u32, alt((line_ending, eof)));
+    let (rest, parsed) = separated_list1(newline, consumed(many0(item)))(input)?;
+
+    let mut offset = 0;
+    let mut line = 1;
+    let mut elves: Vec<Elf> = parsed
+        .into_iter()
+        .enumerate()
+        .map(|(index, (lines, items))| {
+            let start = input.offset(lines);
+            line += input[offset..start].matches('\n').count();
+            offset = start;
+
+            Elf { index, line, items }
+        })
+        .collect();
+
+    while elves.last().is_some_and(|elf| elf.items.is_empty()) {
+        elves.pop();
+    }
+
+    Ok((rest, elves))
+}
+
+/// Parses the elves of `input`, whatever its line endings and trailing
+/// whitespace.
+pub fn parse(input: &str) -> Result<Vec<Elf>, Error> {
+    parse_all(&normalize(input), parse_input)
+}
+
+/// Total calories carried by the `k` elves carrying the most.
+pub fn top_calories(elves: &[Elf], k: usize) -> u64 {
+    let mut top = LimitHeap::with_capacity(k);
+    top.extend(elves.iter().map(Elf::calories));
+    top.iter().sum()
+}
+
+/// The `k` elves carrying the most calories, most first.
+pub fn top_elves(elves: &[Elf], k: usize) -> Vec<&Elf> {
+    let mut top = LimitHeap::by_key(k, |elf: &&Elf| elf.calories());
+    top.extend(elves);
+    top.into_sorted_vec()
+}
+
+/// Percentiles reported by [`analyze`], besides the median.
+const PERCENTILES: [u8; 5] = [10, 25, 75, 90, 99];
+
+/// Statistics about the calories carried by the elves.
+#[derive(Debug, PartialEq)]
+pub struct Analysis<'a> {
+    /// The elves carrying the most calories, most first.
+    pub top: Vec<&'a Elf>,
+    pub mean: f64,
+    pub median: f64,
+    /// Nearest-rank percentiles of the calories carried.
+    pub percentiles: Vec<(u8, u64)>,
+    /// Number of elves carrying each number of items.
+    pub item_counts: BTreeMap<usize, usize>,
+    /// Elves carrying nothing.
+    pub empty: Vec<&'a Elf>,
+}
+
+pub fn analyze(elves: &[Elf], k: usize) -> Result<Analysis<'_>, Error> {
+    if elves.is_empty() {
+        return Err(Error::Invalid("Input contained no elves".to_string()));
+    }
+
+    let mut calories: Vec<u64> = elves.iter().map(Elf::calories).collect();
+    calories.sort_unstable();
+    let count = calories.len();
+
+    let mean = calories.iter().sum::<u64>() as f64 / count as f64;
+    let median = if count.is_multiple_of(2) {
+        (calories[count / 2 - 1] + calories[count / 2]) as f64 / 2.0
+    } else {
+        calories[count / 2] as f64
+    };
+    let percentiles = PERCENTILES
+        .iter()
+        .map(|&percentile| {
+            let rank = (usize::from(percentile) * count).div_ceil(100).max(1);
+            (percentile, calories[rank - 1])
+        })
+        .collect();
+
+    let mut item_counts = BTreeMap::new();
+    for elf in elves {
+        *item_counts.entry(elf.items.len()).or_default() += 1;
+    }
+
+    Ok(Analysis {
+        top: top_elves(elves, k),
+        mean,
+        median,
+        percentiles,
+        item_counts,
+        empty: elves.iter().filter(|elf| elf.items.is_empty()).collect(),
+    })
+}
+
+impl Display for Analysis<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        const BAR_WIDTH: usize = 40;
+
+        writeln!(f, "Top {} elves:", self.top.len())?;
+        for elf in &self.top {
+            writeln!(
+                f,
+                "  elf {} (line {}): {} calories",
+                elf.index,
+                elf.line,
+                elf.calories()
+            )?;
+        }
+
+        writeln!(f, "Mean: {:.2} calories", self.mean)?;
+        writeln!(f, "Median: {:.1} calories", self.median)?;
+        writeln!(f, "Percentiles:")?;
+        for (percentile, calories) in &self.percentiles {
+            writeln!(f, "  {percentile:>2}th: {calories} calories")?;
+        }
+
+        writeln!(f, "Items per elf:")?;
+        let most = self.item_counts.values().copied().max().unwrap_or(0);
+        for (items, elves) in &self.item_counts {
+            let bar = "#".repeat((elves * BAR_WIDTH).div_ceil(most.max(1)));
+            writeln!(f, "  {items:>3}: {elves:>5} {bar}")?;
+        }
+
+        if self.empty.is_empty() {
+            writeln!(f, "Empty elves: none")
+        } else {
+            let empty = self
+                .empty
+                .iter()
+                .map(|elf| format!("elf {} (line {})", elf.index, elf.line))
+                .collect::<Vec<_>>()
+                .join(", ");
+            writeln!(f, "Empty elves: {empty}")
+        }
+    }
+}
+
+/// Splits `input` at blank lines into about `count` chunks of whole elves.
+#[cfg(feature = "rayon")]
+fn split_elves(input: &str, count: usize) -> Vec<&str> {
+    let target = input.len() / count.max(1) + 1;
+    let mut chunks = Vec::new();
+    let mut rest = input;
+
+    loop {
+        let split = rest
+            .get(target..)
+            .and_then(|tail| tail.find("\n\n"))
+            .map(|index| target + index);
+        let Some(split) = split else {
+            chunks.push(rest);
+            return chunks;
+        };
+
+        let (chunk, tail) = rest.split_at(split + 1);
+        chunks.push(chunk);
+        rest = &tail[1..];
     }
 }
 
-fn parse_input(input: &str) -> IResult<&str, Vec<Elf>> {
-    let elf = map(separated_list1(newline, parse_u32), |items| Elf {
-        items: items,
-    });
+/// Same as [`top_calories`] for `K` elves, but parses and collects the elves
+/// of `input` on all cores.
+#[cfg(feature = "rayon")]
+pub fn par_top_calories<const K: usize>(input: &str) -> Result<u64, Error> {
+    use rayon::prelude::*;
+
+    let input = normalize(input);
+    let chunks = split_elves(&input, rayon::current_num_threads() * 4);
+    let Ok(chunks) = chunks
+        .par_iter()
+        .map(|chunk| parse_all(chunk, parse_input))
+        .collect::<Result<Vec<_>, _>>()
+    else {
+        // Chunks do not know which line they start on, so the whole input is
+        // parsed again to report the error.
+        let elves = parse_all(&input, parse_input)?;
+        return Ok(top_calories(&elves, K));
+    };
 
-    separated_list1(count(newline, 2), elf)(input)
+    let top: LimitHeap<_, K> = chunks.par_iter().flatten().map(Elf::calories).collect();
+    Ok(top.iter().sum())
 }
 
 pub struct Solution;
 impl crate::Solution for Solution {
     const DAY: usize = 1;
-    type O1 = u32;
-    type O2 = u32;
+    type O1 = u64;
+    type O2 = u64;
 
-    fn part_one(input: &str) -> Self::O1 {
-        let (_, elves) = parse_input(input).unwrap();
+    fn part_one(input: &str) -> Result<Self::O1, Error> {
+        let elves = parse(input)?;
         elves
             .iter()
             .map(Elf::calories)
             .max()
-            .expect("Input contained no elves")
+            .ok_or_else(|| Error::Invalid("Input contained no elves".to_string()))
     }
 
-    fn part_two(input: &str) -> Self::O2 {
-        let (_, elves) = parse_input(input).unwrap();
-        elves
-            .iter()
-            .map(Elf::calories)
-            .collect::<LimitHeap<_, 3>>()
-            .iter()
-            .sum()
+    #[cfg(not(feature = "rayon"))]
+    fn part_two(input: &str) -> Result<Self::O2, Error> {
+        let elves = parse(input)?;
+        Ok(top_calories(&elves, 3))
+    }
+
+    #[cfg(feature = "rayon")]
+    fn part_two(input: &str) -> Result<Self::O2, Error> {
+        par_top_calories::<3>(input)
     }
 }
 
@@ -76,16 +283,96 @@ mod test {
             elves,
             vec![
                 Elf {
+                    index: 0,
+                    line: 1,
                     items: vec![100, 200]
                 },
-                Elf { items: vec![300] },
                 Elf {
+                    index: 1,
+                    line: 4,
+                    items: vec![300]
+                },
+                Elf {
+                    index: 2,
+                    line: 6,
                     items: vec![400, 500, 600]
                 },
             ]
         );
     }
 
+    #[test]
+    fn parse_empty_elves() {
+        let (_, elves) = super::parse_input("100\n\n\n200\n\n\n").unwrap();
+        let items = elves
+            .iter()
+            .map(|elf| (elf.line, elf.items()))
+            .collect::<Vec<_>>();
+        assert_eq!(items, vec![(1, &[100][..]), (3, &[][..]), (4, &[200][..])]);
+    }
+
+    #[test]
+    fn parse_windows_input() {
+        let windows = "\u{feff}100\r\n200 \r\n\r\n300\r\n\r\n";
+        assert_eq!(super::parse(windows), super::parse("100\n200\n\n300\n"));
+        assert_eq!(super::parse(windows).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn top_elves() {
+        let (_, elves) = super::parse_input("100\n\n300\n\n50\n\n100\n100\n").unwrap();
+        let top = super::top_elves(&elves, 2);
+
+        assert_eq!(
+            top.iter().map(|elf| elf.index()).collect::<Vec<_>>(),
+            vec![1, 3]
+        );
+    }
+
+    #[test]
+    fn analyze() {
+        let input = indoc! {"
+            100
+            200
+
+            500
+
+
+            600
+
+            700
+            100
+        "};
+        let (_, elves) = super::parse_input(input).unwrap();
+        let analysis = super::analyze(&elves, 2).unwrap();
+
+        assert_eq!(analysis.mean, 440.0);
+        assert_eq!(analysis.median, 500.0);
+        assert_eq!(analysis.percentiles[0], (10, 0));
+        assert_eq!(
+            analysis.to_string(),
+            indoc! {"
+                Top 2 elves:
+                  elf 4 (line 9): 800 calories
+                  elf 3 (line 7): 600 calories
+                Mean: 440.00 calories
+                Median: 500.0 calories
+                Percentiles:
+                  10th: 0 calories
+                  25th: 300 calories
+                  75th: 600 calories
+                  90th: 800 calories
+                  99th: 800 calories
+                Items per elf:
+                    0:     1 ####################
+                    1:     2 ########################################
+                    2:     2 ########################################
+                Empty elves: elf 2 (line 6)
+            "}
+        );
+        assert!(super::analyze(&[], 3).is_err());
+    }
+
     #[test]
     fn part_one() {
         let input = indoc! {"
@@ -95,7 +382,7 @@ mod test {
             500
         "};
 
-        assert_eq!(super::Solution::part_one(input), 500);
+        assert_eq!(super::Solution::part_one(input), Ok(500));
     }
 
     #[test]
@@ -113,6 +400,31 @@ mod test {
             100
         "};
 
-        assert_eq!(super::Solution::part_two(input), 1800);
+        assert_eq!(super::Solution::part_two(input), Ok(1800));
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn split_elves() {
+        let input = "1\n2\n\n3\n\n4\n5\n\n6\n";
+
+        assert_eq!(
+            super::split_elves(input, 3),
+            vec!["1\n2\n\n3\n", "4\n5\n\n6\n"]
+        );
+        assert_eq!(super::split_elves("", 3), vec![""]);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_top_calories() {
+        let input = "100\n200\n\n500\n\n600\n\n700\n\n100\n".repeat(100);
+        let (_, elves) = super::parse_input(&input).unwrap();
+
+        assert_eq!(
+            super::par_top_calories::<3>(&input),
+            Ok(super::top_calories(&elves, 3))
+        );
+        assert!(super::par_top_calories::<3>("100\n\nx\n").is_err());
     }
 }
@@ -1,15 +1,18 @@
 use nom::{
     branch::alt,
     bytes::complete::tag,
-    character::complete::{char, line_ending, newline, not_line_ending},
-    combinator::{map, opt},
+    character::complete::{char, line_ending, not_line_ending},
+    combinator::{eof, map, map_opt, opt},
     multi::many0,
     sequence::{delimited, separated_pair, terminated, tuple},
     IResult,
 };
 
+use crate::{error::parse_all, Error};
+
 #[derive(Debug, PartialEq, Eq)]
-enum Filesystem<'a> {
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Filesystem<'a> {
     File {
         name: &'a str,
         size: u32,
@@ -22,11 +25,11 @@ enum Filesystem<'a> {
     },
 }
 
-fn parse_file(input: &str) -> IResult<&str, Filesystem> {
+fn parse_file(input: &str) -> IResult<&str, Filesystem<'_>> {
     map(
         terminated(
             separated_pair(nom::character::complete::u32, char(' '), not_line_ending),
-            newline,
+            alt((line_ending, eof)),
         ),
         |(size, name)| Filesystem::File { name, size },
     )(input)
@@ -36,7 +39,7 @@ fn subdir_listing(input: &str) -> IResult<&str, &str> {
     delimited(tag("dir"), not_line_ending, line_ending)(input)
 }
 
-fn parse_directory(input: &str) -> IResult<&str, Filesystem> {
+pub fn parse_directory(input: &str) -> IResult<&str, Filesystem<'_>> {
     let name = delimited(tag("$ cd "), not_line_ending, tag("\n$ ls\n"));
 
     let children = many0(delimited(
@@ -46,15 +49,17 @@ fn parse_directory(input: &str) -> IResult<&str, Filesystem> {
     ));
     let go_back = opt(tag("$ cd ..\n"));
 
-    map(
+    map_opt(
         tuple((name, terminated(children, go_back))),
         |(name, children)| {
-            let size = children.iter().map(Filesystem::size).sum();
-            Filesystem::Directory {
+            let size = children
+                .iter()
+                .try_fold(0u32, |total, child| total.checked_add(child.size()))?;
+            Some(Filesystem::Directory {
                 name,
                 children,
                 size,
-            }
+            })
         },
     )(input)
 }
@@ -67,7 +72,7 @@ impl<'a> Filesystem<'a> {
         }
     }
 
-    fn children(&self) -> impl Iterator<Item = &Filesystem> {
+    fn children(&self) -> impl Iterator<Item = &Filesystem<'a>> {
         let children = match self {
             Filesystem::Directory { children, .. } => Some(children),
             _ => None,
@@ -76,7 +81,7 @@ impl<'a> Filesystem<'a> {
         children.into_iter().flatten()
     }
 
-    fn iter(&'a self) -> Box<dyn Iterator<Item = &Filesystem> + 'a> {
+    fn iter(&'a self) -> Box<dyn Iterator<Item = &'a Filesystem<'a>> + 'a> {
         Box::new(std::iter::once(self).chain(self.children().flat_map(Self::iter)))
     }
 
@@ -91,29 +96,31 @@ impl crate::Solution for Solution {
     type O1 = u32;
     type O2 = u32;
 
-    fn part_one(input: &str) -> Self::O1 {
-        let (_, filesystem) = parse_directory(input).unwrap();
-        filesystem
+    fn part_one(input: &str) -> Result<Self::O1, Error> {
+        let filesystem = parse_all(input, parse_directory)?;
+        Ok(filesystem
             .iter()
             .filter(|x| x.is_directory())
             .map(Filesystem::size)
             .filter(|size| *size <= 100_000)
-            .sum()
+            .sum())
     }
 
-    fn part_two(input: &str) -> Self::O2 {
-        let (_, filesystem) = parse_directory(input).unwrap();
+    fn part_two(input: &str) -> Result<Self::O2, Error> {
+        let filesystem = parse_all(input, parse_directory)?;
         const TOTAL_SPACE: u32 = 70_000_000;
         const UPDATE_SIZE: u32 = 30_000_000;
-        let used_space = filesystem.size();
+        let free_space = TOTAL_SPACE
+            .checked_sub(filesystem.size())
+            .ok_or_else(|| Error::Invalid("Filesystem is larger than the disk".to_string()))?;
 
         filesystem
             .iter()
             .filter(|x| x.is_directory())
             .map(Filesystem::size)
-            .filter(|size| (TOTAL_SPACE - used_space + size) >= UPDATE_SIZE)
+            .filter(|size| (free_space + size) >= UPDATE_SIZE)
             .min()
-            .unwrap()
+            .ok_or_else(|| Error::Invalid("No directory frees enough space".to_string()))
     }
 }
 
@@ -122,6 +129,52 @@ mod test {
     use super::Filesystem;
     use crate::Solution;
     use indoc::indoc;
+    use proptest::prelude::*;
+
+    const NAMES: [&str; 6] = ["a", "b", "foo", "bar.txt", "baz.dat", "x.y.z"];
+
+    fn render(directory: &Filesystem) -> String {
+        let Filesystem::Directory { name, children, .. } = directory else {
+            panic!("Transcripts start in a directory")
+        };
+
+        let mut transcript = format!("$ cd {name}\n$ ls\n");
+        for child in children {
+            if let Filesystem::Directory { name, .. } = child {
+                transcript += &format!("dir {name}\n");
+            }
+        }
+        for child in children {
+            match child {
+                Filesystem::File { name, size } => transcript += &format!("{size} {name}\n"),
+                Filesystem::Directory { .. } => {
+                    transcript += &render(child);
+                    transcript += "$ cd ..\n";
+                }
+            }
+        }
+        transcript
+    }
+
+    fn directory(
+        children: impl Strategy<Value = Filesystem<'static>>,
+    ) -> impl Strategy<Value = Filesystem<'static>> {
+        let name = prop::sample::select(&NAMES[..]);
+        (name, prop::collection::vec(children, 0..5)).prop_map(|(name, children)| {
+            let size = children.iter().map(Filesystem::size).sum();
+            Filesystem::Directory {
+                name,
+                children,
+                size,
+            }
+        })
+    }
+
+    fn filesystem() -> impl Strategy<Value = Filesystem<'static>> {
+        let file = (prop::sample::select(&NAMES[..]), 0..1_000_000u32)
+            .prop_map(|(name, size)| Filesystem::File { name, size });
+        directory(file.prop_recursive(4, 32, 5, directory))
+    }
 
     #[test]
     fn parse_file() {
@@ -180,6 +233,7 @@ mod test {
         );
     }
 
+    #[allow(clippy::redundant_static_lifetimes)]
     const SAMPLE_INPUT: &'static str = indoc! {"
         $ cd /
         $ ls
@@ -209,12 +263,24 @@ mod test {
     #[test]
     fn part_one() {
         let result = super::Solution::part_one(SAMPLE_INPUT);
-        assert_eq!(result, 95437);
+        assert_eq!(result, Ok(95437));
     }
 
     #[test]
     fn part_two() {
         let result = super::Solution::part_two(SAMPLE_INPUT);
-        assert_eq!(result, 24933642);
+        assert_eq!(result, Ok(24933642));
+    }
+
+    proptest! {
+        #[test]
+        fn transcript_round_trip(filesystem in filesystem()) {
+            let transcript = render(&filesystem);
+            let (rest, parsed) = super::parse_directory(&transcript).unwrap();
+
+            prop_assert_eq!(rest, "");
+            prop_assert_eq!(&parsed, &filesystem);
+            prop_assert_eq!(render(&parsed), transcript);
+        }
     }
 }
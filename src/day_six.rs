@@ -1,7 +1,9 @@
 use heapless::Deque;
 use itertools::Itertools;
 
-fn find_unique_sequence<const N: usize>(input: &str) -> Option<usize> {
+use crate::Error;
+
+pub fn find_unique_sequence<const N: usize>(input: &str) -> Option<usize> {
     let mut unique_chars = 0;
     let mut queue: Deque<char, N> = Deque::new();
 
@@ -32,11 +34,15 @@ impl crate::Solution for Solution {
     type O1 = usize;
     type O2 = usize;
 
-    fn part_one(input: &str) -> Self::O1 {
-        find_unique_sequence::<4>(input).unwrap() + 1
+    fn part_one(input: &str) -> Result<Self::O1, Error> {
+        find_unique_sequence::<4>(input)
+            .map(|index| index + 1)
+            .ok_or_else(|| Error::Invalid("No start-of-packet marker".to_string()))
     }
 
-    fn part_two(input: &str) -> Self::O2 {
-        find_unique_sequence::<14>(input).unwrap() + 1
+    fn part_two(input: &str) -> Result<Self::O2, Error> {
+        find_unique_sequence::<14>(input)
+            .map(|index| index + 1)
+            .ok_or_else(|| Error::Invalid("No start-of-message marker".to_string()))
     }
 }
@@ -10,8 +10,11 @@ use nom::{
     IResult,
 };
 
+use crate::{error::complete, Error};
+
 #[derive(Clone, Copy)]
-enum Instruction {
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Instruction {
     Noop,
     Addx(i32),
 }
@@ -24,15 +27,15 @@ impl Instruction {
         }
     }
 
-    fn apply(&self, register: &mut i32) {
+    fn apply(&self, register: &mut i64) {
         match self {
             Instruction::Noop => {},
-            Instruction::Addx(x) => *register += x,
+            Instruction::Addx(x) => *register += i64::from(*x),
         }
     }
 }
 
-fn parse_instruction(input: &str) -> IResult<&str, Instruction> {
+pub fn parse_instruction(input: &str) -> IResult<&str, Instruction> {
     let noop = value(Instruction::Noop, tag("noop"));
     let add = map(preceded(tag("addx "), nom::character::complete::i32), |x| {
         Instruction::Addx(x)
@@ -40,7 +43,8 @@ fn parse_instruction(input: &str) -> IResult<&str, Instruction> {
     terminated(alt((noop, add)), opt(newline))(input)
 }
 
-fn register_values(instructions: impl Iterator<Item = Instruction>) -> impl Iterator<Item = i32> {
+#[allow(clippy::manual_repeat_n)]
+fn register_values(instructions: impl Iterator<Item = Instruction>) -> impl Iterator<Item = i64> {
     instructions
         .scan(1, |x, instruction| {
             let current = *x; 
@@ -52,22 +56,29 @@ fn register_values(instructions: impl Iterator<Item = Instruction>) -> impl Iter
 pub struct Solution;
 impl crate::Solution for Solution {
     const DAY: usize = 10;
-    type O1 = i32;
+    type O1 = i64;
     type O2 = String;
 
-    fn part_one(input: &str) -> Self::O1 {
+    fn part_one(input: &str) -> Result<Self::O1, Error> {
         let mut instructions = iterator(input, parse_instruction);
-        let signals = register_values(&mut instructions)
+        // Fused, since the parser iterator breaks when polled after its end.
+        let mut parsed = (&mut instructions).fuse();
+        let signals = register_values(&mut parsed)
             .enumerate()
-            .map(|(i, x)| (i as i32 + 1) * x);
+            .map(|(i, x)| (i as i64 + 1) * x);
+
+        let strength = signals.skip(19).step_by(40).take(6).sum();
 
-        signals.skip(19).step_by(40).take(6).sum()
+        // Only the first 220 cycles count, but the rest must still parse.
+        parsed.for_each(drop);
+        complete(input, instructions.finish())?;
+        Ok(strength)
     }
 
-    fn part_two(input: &str) -> Self::O2 {
+    fn part_two(input: &str) -> Result<Self::O2, Error> {
         let mut instructions = iterator(input, parse_instruction);
         let sprite_positions = register_values(&mut instructions);
-        let ray_position = repeat(1..=40).flatten();
+        let ray_position = repeat(1..=40i64).flatten();
 
         let pixels = sprite_positions
             .zip(ray_position)
@@ -83,7 +94,8 @@ impl crate::Solution for Solution {
             .map(|line| line.collect::<String>())
             .join("\n");
 
-        format!("\n\n{pixels}\n\n")
+        complete(input, instructions.finish())?;
+        Ok(format!("\n\n{pixels}\n\n"))
     }
 }
 
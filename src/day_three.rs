@@ -1,11 +1,151 @@
-use itertools::Itertools;
-use std::{collections::HashSet, hash::Hash};
+use std::{
+    collections::HashSet,
+    fmt::{self, Display},
+    hash::Hash,
+};
 
-fn get_duplicate_item(input: &str) -> Option<char> {
-    let (left, right) = input.split_at(input.len() / 2);
-    find_common_items([left.chars(), right.chars()])
-        .into_iter()
-        .next()
+use crate::Error;
+
+/// A set of items, stored as one bit per priority.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ItemSet(u64);
+
+impl ItemSet {
+    /// The items of the rucksack on `line`, failing on anything that is not an
+    /// item.
+    pub fn parse(line: usize, rucksack: &str) -> Result<Self, Error> {
+        rucksack.chars().try_fold(Self::default(), |set, item| {
+            let priority = item_priority(line, item)?;
+            Ok(Self(set.0 | 1 << priority))
+        })
+    }
+
+    pub fn contains(self, item: char) -> bool {
+        get_item_priority(item).is_some_and(|priority| self.0 & 1 << priority != 0)
+    }
+
+    pub fn intersection(self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    pub fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Priorities of the items, lowest first.
+    pub fn priorities(self) -> impl Iterator<Item = u32> {
+        let mut bits = self.0;
+        std::iter::from_fn(move || {
+            let priority = bits.trailing_zeros();
+            bits &= bits.checked_sub(1)?;
+            Some(priority)
+        })
+    }
+
+    /// The items, by increasing priority.
+    pub fn iter(self) -> impl Iterator<Item = char> {
+        self.priorities().map(|priority| match priority as u8 {
+            priority @ 1..=26 => char::from(b'a' + priority - 1),
+            priority => char::from(b'A' + priority - 27),
+        })
+    }
+}
+
+/// Splits the rucksack on `line` into `count` compartments of equal size.
+pub fn compartments(line: usize, rucksack: &str, count: usize) -> Result<Vec<ItemSet>, Error> {
+    // Only ASCII letters are items, so byte offsets below are characters.
+    ItemSet::parse(line, rucksack)?;
+    if count == 0 || !rucksack.len().is_multiple_of(count) {
+        return Err(Error::Parse(format!(
+            "line {line}: {} items cannot be split into {count} equal compartments",
+            rucksack.len()
+        )));
+    }
+
+    let size = rucksack.len() / count;
+    (0..count)
+        .map(|index| ItemSet::parse(line, &rucksack[index * size..(index + 1) * size]))
+        .collect()
+}
+
+/// Items found in at least `k` of the `compartments`.
+pub fn shared_by(compartments: &[ItemSet], k: usize) -> ItemSet {
+    let all = compartments
+        .iter()
+        .copied()
+        .fold(ItemSet::default(), ItemSet::union);
+
+    ItemSet(
+        all.priorities()
+            .filter(|priority| {
+                let holding = compartments.iter().filter(|c| c.0 & 1 << priority != 0);
+                holding.count() >= k
+            })
+            .fold(0, |bits, priority| bits | 1 << priority),
+    )
+}
+
+/// The compartments of one rucksack and the items they share.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Breakdown {
+    pub line: usize,
+    pub compartments: Vec<ItemSet>,
+    /// Items found in every compartment.
+    pub shared_by_all: ItemSet,
+    /// Items found in at least `k` compartments.
+    pub shared_by_k: ItemSet,
+    pub k: usize,
+}
+
+impl Display for Breakdown {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let compartments = self
+            .compartments
+            .iter()
+            .map(|items| items.iter().collect::<String>())
+            .collect::<Vec<_>>()
+            .join(" | ");
+        let all: String = self.shared_by_all.iter().collect();
+        let k: String = self.shared_by_k.iter().collect();
+
+        write!(
+            f,
+            "line {}: {compartments}; all: {all}; at least {}: {k}",
+            self.line, self.k
+        )
+    }
+}
+
+/// Splits every rucksack of `input` into `count` compartments, and finds the
+/// items shared by all of them and by at least `k` of them.
+pub fn breakdown(input: &str, count: usize, k: usize) -> Result<Vec<Breakdown>, Error> {
+    input
+        .lines()
+        .zip(1..)
+        .map(|(rucksack, line)| {
+            let compartments = compartments(line, rucksack, count)?;
+            Ok(Breakdown {
+                line,
+                shared_by_all: shared_by(&compartments, count),
+                shared_by_k: shared_by(&compartments, k),
+                compartments,
+                k,
+            })
+        })
+        .collect()
+}
+
+fn get_duplicate_item(line: usize, rucksack: &str) -> Result<Option<char>, Error> {
+    let compartments = compartments(line, rucksack, 2)?;
+    Ok(shared_by(&compartments, 2).iter().next())
 }
 
 fn get_item_priority(input: char) -> Option<u8> {
@@ -16,7 +156,15 @@ fn get_item_priority(input: char) -> Option<u8> {
     }
 }
 
-fn find_common_items<I, T>(bags: impl IntoIterator<Item = I>) -> HashSet<T>
+fn item_priority(line: usize, item: char) -> Result<u32, Error> {
+    get_item_priority(item)
+        .map(u32::from)
+        .ok_or_else(|| Error::Parse(format!("line {line}: `{item}` is not an item")))
+}
+
+/// Items found in every one of the `bags`, for items that do not fit in an
+/// [`ItemSet`].
+pub fn find_common_items<I, T>(bags: impl IntoIterator<Item = I>) -> HashSet<T>
 where
     HashSet<T>: FromIterator<T>,
     I: IntoIterator<Item = T>,
@@ -28,37 +176,135 @@ where
         .unwrap_or_default()
 }
 
+/// A group of consecutive rucksacks and the items all of them carry.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Group {
+    /// Line of the group's first rucksack.
+    pub line: usize,
+    pub rucksacks: usize,
+    pub common: ItemSet,
+}
+
+impl Group {
+    /// The one item carried by every rucksack of the group.
+    pub fn badge(&self) -> Result<char, Error> {
+        let mut common = self.common.iter();
+        match (common.next(), common.next()) {
+            (Some(badge), None) => Ok(badge),
+            (None, _) => Err(Error::Invalid(format!(
+                "line {}: group has no item in common",
+                self.line
+            ))),
+            (Some(_), Some(_)) => Err(Error::Invalid(format!(
+                "line {}: group has several items in common: {}",
+                self.line,
+                self.common
+                    .iter()
+                    .map(|item| format!("`{item}`"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))),
+        }
+    }
+}
+
+impl Display for Group {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let last = self.line + self.rucksacks - 1;
+        write!(f, "lines {}-{last}: ", self.line)?;
+        if self.common.is_empty() {
+            return write!(f, "no item in common");
+        }
+
+        let items = self
+            .common
+            .iter()
+            .zip(self.common.priorities())
+            .map(|(item, priority)| format!("{item} ({priority})"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        write!(f, "{items}")
+    }
+}
+
+/// Splits the rucksacks of `input` into groups of `size`.
+pub fn groups(input: &str, size: usize) -> Result<Vec<Group>, Error> {
+    if size == 0 {
+        return Err(Error::Invalid(
+            "Groups need at least one rucksack".to_string(),
+        ));
+    }
+
+    let rucksacks = input
+        .lines()
+        .zip(1..)
+        .map(|(rucksack, line)| Ok((line, ItemSet::parse(line, rucksack)?)))
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    rucksacks
+        .chunks(size)
+        .map(|group| {
+            let line = group[0].0;
+            if group.len() < size {
+                return Err(Error::Invalid(format!(
+                    "line {line}: group has {} rucksacks instead of {size}",
+                    group.len()
+                )));
+            }
+
+            let common = group
+                .iter()
+                .map(|(_, items)| *items)
+                .reduce(ItemSet::intersection)
+                .unwrap_or_default();
+            Ok(Group {
+                line,
+                rucksacks: group.len(),
+                common,
+            })
+        })
+        .collect()
+}
+
+/// Sum of the badge priorities of every group of `size` rucksacks.
+pub fn badge_priorities(input: &str, size: usize) -> Result<u32, Error> {
+    groups(input, size)?
+        .iter()
+        .map(|group| item_priority(group.line, group.badge()?))
+        .sum()
+}
+
 pub struct Solution;
 impl crate::Solution for Solution {
     const DAY: usize = 3;
     type O1 = u32;
     type O2 = u32;
 
-    fn part_one(input: &str) -> Self::O1 {
+    fn part_one(input: &str) -> Result<Self::O1, Error> {
         input
             .lines()
-            .map(|s| get_duplicate_item(s).expect("Rucksack did not contain duplicate a duplicate"))
-            .map(|i| get_item_priority(i).expect("Duplicate item does not have a defined priority"))
-            .map(u32::from)
+            .zip(1..)
+            .map(|(rucksack, line)| {
+                let item = get_duplicate_item(line, rucksack)?.ok_or_else(|| {
+                    Error::Invalid(format!("line {line}: no item is in both compartments"))
+                })?;
+                item_priority(line, item)
+            })
             .sum()
     }
 
-    fn part_two(input: &str) -> Self::O2 {
-        input
-            .lines()
-            .map(|line| line.chars().collect::<HashSet<char>>())
-            .chunks(3)
-            .into_iter()
-            .map(find_common_items)
-            .map(|common_items| common_items.into_iter().next().expect("No common items"))
-            .map(|i| get_item_priority(i).expect("Duplicate item does not have a defined priority"))
-            .map(u32::from)
-            .sum()
+    fn part_two(input: &str) -> Result<Self::O2, Error> {
+        badge_priorities(input, 3)
     }
 }
 
 #[cfg(test)]
 mod test {
+    use indoc::indoc;
+
+    use super::ItemSet;
+    use crate::Error;
+
     #[test]
     fn get_item_priority() {
         assert_eq!(super::get_item_priority('a'), Some(1));
@@ -73,8 +319,116 @@ mod test {
 
     #[test]
     fn get_duplicate_item() {
-        assert_eq!(super::get_duplicate_item("abcdef"), None);
-        assert_eq!(super::get_duplicate_item("abcdea"), Some('a'));
+        assert_eq!(super::get_duplicate_item(1, "abcdef"), Ok(None));
+        assert_eq!(super::get_duplicate_item(1, "abcdea"), Ok(Some('a')));
+        assert!(super::get_duplicate_item(1, "ab1ab1").is_err());
+        assert!(super::get_duplicate_item(1, "abcab").is_err());
+        assert!(super::get_duplicate_item(1, "aé").is_err());
+    }
+
+    #[test]
+    fn compartments() {
+        let compartments = super::compartments(1, "abcbcdcde", 3).unwrap();
+        let items: Vec<String> = compartments
+            .iter()
+            .map(|items| items.iter().collect())
+            .collect();
+        assert_eq!(items, vec!["abc", "bcd", "cde"]);
+
+        assert_eq!(
+            super::shared_by(&compartments, 3)
+                .iter()
+                .collect::<String>(),
+            "c"
+        );
+        assert_eq!(
+            super::shared_by(&compartments, 2)
+                .iter()
+                .collect::<String>(),
+            "bcd"
+        );
+        assert_eq!(
+            super::compartments(4, "abcd", 3),
+            Err(Error::Parse(
+                "line 4: 4 items cannot be split into 3 equal compartments".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn breakdown() {
+        let breakdown = super::breakdown("abcbcdcde\nxyzxyzxyz\n", 3, 2).unwrap();
+        assert_eq!(
+            breakdown[0].to_string(),
+            "line 1: abc | bcd | cde; all: c; at least 2: bcd"
+        );
+        assert_eq!(breakdown[1].shared_by_all.len(), 3);
+        assert!(super::breakdown("ab\nabc\n", 2, 2).is_err());
+    }
+
+    #[test]
+    fn item_set() {
+        let left = ItemSet::parse(1, "vJrwpWtwJgWr").unwrap();
+        let right = ItemSet::parse(1, "hcsFMMfFFhFp").unwrap();
+
+        assert_eq!(left.len(), 8);
+        assert!(left.contains('J') && !left.contains('h') && !left.contains('1'));
+        assert_eq!(left.intersection(right).iter().collect::<String>(), "p");
+        assert_eq!(
+            left.intersection(right).priorities().collect::<Vec<_>>(),
+            vec![16]
+        );
+        assert_eq!(left.union(right).len(), 14);
+        assert!(ItemSet::default().is_empty());
+        assert_eq!(
+            ItemSet::parse(1, "zaZA")
+                .unwrap()
+                .iter()
+                .collect::<String>(),
+            "azAZ"
+        );
+    }
+
+    #[test]
+    fn groups() {
+        let input = indoc! {"
+            vJrwpWtwJgWrhcsFMMfFFhFp
+            jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+            PmmdzqPrVvPwwTWBwg
+            wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+            ttgJtRGJQctTZtZT
+            CrZsJsPPZsGzwwsLwLmpwMDw
+        "};
+
+        let groups = super::groups(input, 3).unwrap();
+        assert_eq!(groups[0].to_string(), "lines 1-3: r (18)");
+        assert_eq!(groups[1].to_string(), "lines 4-6: Z (52)");
+        assert_eq!(super::badge_priorities(input, 3), Ok(70));
+
+        let pairs = super::groups(input, 2).unwrap();
+        assert_eq!(
+            pairs[0].to_string(),
+            "lines 1-2: f (6), r (18), s (19), F (32), M (39)"
+        );
+        assert_eq!(
+            super::badge_priorities(input, 2),
+            Err(Error::Invalid(
+                "line 1: group has several items in common: `f`, `r`, `s`, `F`, `M`".to_string()
+            ))
+        );
+        assert_eq!(
+            super::badge_priorities(input, 6),
+            Err(Error::Invalid(
+                "line 1: group has no item in common".to_string()
+            ))
+        );
+        assert_eq!(
+            super::badge_priorities(input, 4),
+            Err(Error::Invalid(
+                "line 5: group has 2 rucksacks instead of 4".to_string()
+            ))
+        );
+        assert!(super::groups(input, 0).is_err());
     }
 
     #[test]
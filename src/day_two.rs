@@ -1,120 +1,246 @@
-use std::cmp::Ordering;
+use std::fmt::{self, Display};
 
+use itertools::Itertools;
 use nom::{
     branch::alt,
-    character::complete::{char, newline},
-    combinator::value,
+    character::complete::{anychar, char, newline},
+    combinator::{map_opt, value},
     multi::separated_list1,
     sequence::separated_pair,
     IResult,
 };
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
-enum Choice {
-    Rock,
-    Paper,
-    Scissors,
-}
+use crate::{error::parse_all, Error};
 
-impl Choice {
-    fn wins_to(&self) -> Self {
-        match self {
-            Self::Rock => Self::Scissors,
-            Self::Paper => Self::Rock,
-            Self::Scissors => Self::Paper,
-        }
-    }
+pub mod simulation;
 
-    fn loses_to(&self) -> Self {
-        match self {
-            Self::Rock => Self::Paper,
-            Self::Paper => Self::Scissors,
-            Self::Scissors => Self::Rock,
-        }
-    }
-}
-
-impl Ord for Choice {
-    fn cmp(&self, other: &Self) -> Ordering {
-        if self.wins_to() == *other {
-            Ordering::Greater
-        } else if self == other {
-            Ordering::Equal
-        } else {
-            Ordering::Less
-        }
-    }
-}
+/// A choice in a [`Game`], identified by its position in the game's rules.
+/// Choices come from [`Game::choices`], [`Game::choice`] and the parsers, so
+/// they always exist in their game.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Choice(usize);
 
-impl PartialOrd for Choice {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
+/// The first three choices, which every game has.
+impl Choice {
+    pub const ROCK: Self = Self(0);
+    pub const PAPER: Self = Self(1);
+    pub const SCISSORS: Self = Self(2);
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-enum Outcome {
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Outcome {
     Win,
     Lose,
     Draw,
 }
 
-fn score_round(round: (Choice, Choice)) -> u32 {
-    let (opponents_choice, my_choice) = round;
+/// Points awarded for the outcome of a round.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OutcomeScores {
+    pub lose: u32,
+    pub draw: u32,
+    pub win: u32,
+}
 
-    let choice_score = match my_choice {
-        Choice::Rock => 1,
-        Choice::Paper => 2,
-        Choice::Scissors => 3,
-    };
+/// A cyclic hand game with an odd number of choices, where every choice beats
+/// half of the others and loses to the other half.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Game {
+    names: Vec<String>,
+    choice_scores: Vec<u32>,
+    /// `beats[a][b]` is set if `a` beats `b`.
+    beats: Vec<Vec<bool>>,
+    outcome_scores: OutcomeScores,
+}
 
-    let result_score = match my_choice.cmp(&opponents_choice) {
-        Ordering::Less => 0,
-        Ordering::Equal => 3,
-        Ordering::Greater => 6,
-    };
+impl Game {
+    /// Builds a game from its rules table: the name and score of every choice,
+    /// the `(winner, loser)` pairs of choice indexes and the outcome scores.
+    pub fn new(
+        choices: &[(&str, u32)],
+        beats: &[(usize, usize)],
+        outcome_scores: OutcomeScores,
+    ) -> Result<Self, Error> {
+        let count = choices.len();
+        if count.is_multiple_of(2) || !(3..=26).contains(&count) {
+            return Err(Error::Invalid(format!(
+                "A game needs an odd number of choices from 3 to 26, not {count}"
+            )));
+        }
 
-    choice_score + result_score
-}
+        let mut table = vec![vec![false; count]; count];
+        for &(winner, loser) in beats {
+            if winner >= count || loser >= count {
+                return Err(Error::Invalid(format!(
+                    "Choice {} does not exist",
+                    winner.max(loser)
+                )));
+            }
+            if winner == loser || table[loser][winner] {
+                return Err(Error::Invalid(format!(
+                    "`{}` cannot both beat and lose to `{}`",
+                    choices[winner].0, choices[loser].0
+                )));
+            }
+            table[winner][loser] = true;
+        }
 
-fn apply_strategy(round: (Choice, Outcome)) -> (Choice, Choice) {
-    let (opponent_choice, desired_outcome) = round;
+        for (choice, row) in table.iter().enumerate() {
+            let wins = row.iter().filter(|beats| **beats).count();
+            let losses = table.iter().filter(|other| other[choice]).count();
+            if wins != count / 2 || losses != count / 2 {
+                return Err(Error::Invalid(format!(
+                    "`{}` beats {wins} and loses to {losses} choices, instead of {} each",
+                    choices[choice].0,
+                    count / 2
+                )));
+            }
+        }
 
-    let my_choice = match desired_outcome {
-        Outcome::Win => opponent_choice.loses_to(),
-        Outcome::Lose => opponent_choice.wins_to(),
-        Outcome::Draw => opponent_choice.clone(),
+        Ok(Self {
+            names: choices.iter().map(|(name, _)| name.to_string()).collect(),
+            choice_scores: choices.iter().map(|(_, score)| *score).collect(),
+            beats: table,
+            outcome_scores,
+        })
+    }
+
+    const OUTCOME_SCORES: OutcomeScores = OutcomeScores {
+        lose: 0,
+        draw: 3,
+        win: 6,
     };
 
-    (opponent_choice, my_choice)
+    pub fn rock_paper_scissors() -> Self {
+        Self::new(
+            &[("Rock", 1), ("Paper", 2), ("Scissors", 3)],
+            &[(0, 2), (1, 0), (2, 1)],
+            Self::OUTCOME_SCORES,
+        )
+        .expect("rock paper scissors is a valid game")
+    }
+
+    pub fn rock_paper_scissors_lizard_spock() -> Self {
+        let beats = [
+            (0, 2), // Rock crushes scissors
+            (0, 3), // Rock crushes lizard
+            (1, 0), // Paper covers rock
+            (1, 4), // Paper disproves Spock
+            (2, 1), // Scissors cuts paper
+            (2, 3), // Scissors decapitates lizard
+            (3, 1), // Lizard eats paper
+            (3, 4), // Lizard poisons Spock
+            (4, 0), // Spock vaporizes rock
+            (4, 2), // Spock smashes scissors
+        ];
+
+        Self::new(
+            &[
+                ("Rock", 1),
+                ("Paper", 2),
+                ("Scissors", 3),
+                ("Lizard", 4),
+                ("Spock", 5),
+            ],
+            &beats,
+            Self::OUTCOME_SCORES,
+        )
+        .expect("rock paper scissors lizard Spock is a valid game")
+    }
+
+    /// Number of choices.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn choices(&self) -> impl Iterator<Item = Choice> {
+        (0..self.len()).map(Choice)
+    }
+
+    /// The choice at `index` in the rules table, if there is one.
+    pub fn choice(&self, index: usize) -> Option<Choice> {
+        (index < self.len()).then_some(Choice(index))
+    }
+
+    pub fn name(&self, choice: Choice) -> &str {
+        &self.names[choice.0]
+    }
+
+    /// Outcome of playing `mine` against `opponents`.
+    pub fn outcome(&self, mine: Choice, opponents: Choice) -> Outcome {
+        if self.beats[mine.0][opponents.0] {
+            Outcome::Win
+        } else if self.beats[opponents.0][mine.0] {
+            Outcome::Lose
+        } else {
+            Outcome::Draw
+        }
+    }
+
+    pub fn score_round(&self, round: (Choice, Choice)) -> u32 {
+        let (opponents_choice, my_choice) = round;
+
+        let result_score = match self.outcome(my_choice, opponents_choice) {
+            Outcome::Lose => self.outcome_scores.lose,
+            Outcome::Draw => self.outcome_scores.draw,
+            Outcome::Win => self.outcome_scores.win,
+        };
+
+        self.choice_scores[my_choice.0] + result_score
+    }
+
+    /// Picks my choice for the desired outcome. If several choices lead to it,
+    /// the one scoring the most is picked.
+    pub fn apply_strategy(&self, round: (Choice, Outcome)) -> (Choice, Choice) {
+        let (opponent_choice, desired_outcome) = round;
+
+        let my_choice = self
+            .choices()
+            .filter(|mine| self.outcome(*mine, opponent_choice) == desired_outcome)
+            .max_by_key(|mine| (self.choice_scores[mine.0], std::cmp::Reverse(mine.0)))
+            .expect("every outcome is possible against every choice");
+
+        (opponent_choice, my_choice)
+    }
 }
 
-fn parse_rounds(input: &str) -> IResult<&str, Vec<(Choice, Choice)>> {
-    let opponent_choice = alt((
-        value(Choice::Rock, char('A')),
-        value(Choice::Paper, char('B')),
-        value(Choice::Scissors, char('C')),
-    ));
+/// A choice coded as one of `choices` consecutive letters starting at `first`.
+fn choice<'a>(first: char, choices: usize) -> impl FnMut(&'a str) -> IResult<&'a str, Choice> {
+    map_opt(anychar, move |c| {
+        let index = (c as usize).checked_sub(first as usize)?;
+        (index < choices).then_some(Choice(index))
+    })
+}
 
-    let my_choice = alt((
-        value(Choice::Rock, char('X')),
-        value(Choice::Paper, char('Y')),
-        value(Choice::Scissors, char('Z')),
-    ));
+/// The opponent's choices are coded from `A`.
+fn opponent_choice<'a>(game: &Game) -> impl FnMut(&'a str) -> IResult<&'a str, Choice> {
+    choice('A', game.len())
+}
+
+/// My choices are coded as the letters ending at `Z`, so `X`, `Y` and `Z` for
+/// rock paper scissors.
+fn my_choice<'a>(game: &Game) -> impl FnMut(&'a str) -> IResult<&'a str, Choice> {
+    choice(char::from(b'Z' + 1 - game.len() as u8), game.len())
+}
 
+pub fn parse_game_rounds<'a>(
+    game: &Game,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<(Choice, Choice)>> {
     separated_list1(
         newline,
-        separated_pair(opponent_choice, char(' '), my_choice),
-    )(input)
+        separated_pair(opponent_choice(game), char(' '), my_choice(game)),
+    )
 }
 
-fn parse_desired_outcomes(input: &str) -> IResult<&str, Vec<(Choice, Outcome)>> {
-    let opponent_choice = alt((
-        value(Choice::Rock, char('A')),
-        value(Choice::Paper, char('B')),
-        value(Choice::Scissors, char('C')),
-    ));
-
+pub fn parse_game_desired_outcomes<'a>(
+    game: &Game,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<(Choice, Outcome)>> {
     let desired_outcome = alt((
         value(Outcome::Lose, char('X')),
         value(Outcome::Draw, char('Y')),
@@ -123,8 +249,160 @@ fn parse_desired_outcomes(input: &str) -> IResult<&str, Vec<(Choice, Outcome)>>
 
     separated_list1(
         newline,
-        separated_pair(opponent_choice, char(' '), desired_outcome),
-    )(input)
+        separated_pair(opponent_choice(game), char(' '), desired_outcome),
+    )
+}
+
+/// Rounds of rock paper scissors.
+pub fn parse_rounds(input: &str) -> IResult<&str, Vec<(Choice, Choice)>> {
+    parse_game_rounds(&Game::rock_paper_scissors())(input)
+}
+
+/// Desired outcomes of rounds of rock paper scissors.
+pub fn parse_desired_outcomes(input: &str) -> IResult<&str, Vec<(Choice, Outcome)>> {
+    parse_game_desired_outcomes(&Game::rock_paper_scissors())(input)
+}
+
+/// A way of reading the second column of the strategy guide.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Interpretation {
+    /// The n-th letter is the n-th of these choices.
+    Choices(Vec<Choice>),
+    /// The n-th letter is the n-th of these desired outcomes.
+    Outcomes(Vec<Outcome>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Evaluation {
+    pub interpretation: Interpretation,
+    /// Total score of following the guide read this way.
+    pub score: u32,
+}
+
+/// The total score of every interpretation of a strategy guide.
+pub struct Exploration<'a> {
+    game: &'a Game,
+    pub evaluations: Vec<Evaluation>,
+}
+
+impl Exploration<'_> {
+    pub fn highest(&self) -> &Evaluation {
+        self.evaluations
+            .iter()
+            .max_by_key(|evaluation| evaluation.score)
+            .expect("every game has an interpretation")
+    }
+
+    pub fn lowest(&self) -> &Evaluation {
+        self.evaluations
+            .iter()
+            .min_by_key(|evaluation| evaluation.score)
+            .expect("every game has an interpretation")
+    }
+
+    fn describe(&self, interpretation: &Interpretation) -> String {
+        let first = b'Z' + 1 - self.game.len() as u8;
+        let meanings: Vec<String> = match interpretation {
+            Interpretation::Choices(choices) => choices
+                .iter()
+                .map(|choice| self.game.name(*choice).to_string())
+                .collect(),
+            Interpretation::Outcomes(outcomes) => outcomes
+                .iter()
+                .map(|outcome| format!("{outcome:?}"))
+                .collect(),
+        };
+
+        meanings
+            .iter()
+            .zip(first..)
+            .map(|(meaning, letter)| format!("{}={meaning}", char::from(letter)))
+            .join(" ")
+    }
+}
+
+impl Display for Exploration<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (title, outcomes) in [("choices", false), ("outcomes", true)] {
+            let mut evaluations = self
+                .evaluations
+                .iter()
+                .filter(|evaluation| {
+                    matches!(evaluation.interpretation, Interpretation::Outcomes(_)) == outcomes
+                })
+                .peekable();
+            if evaluations.peek().is_none() {
+                continue;
+            }
+
+            writeln!(f, "Second column as {title}:")?;
+            for evaluation in evaluations {
+                let interpretation = self.describe(&evaluation.interpretation);
+                writeln!(f, "  {interpretation}: {}", evaluation.score)?;
+            }
+        }
+
+        for (title, evaluation) in [("Highest", self.highest()), ("Lowest", self.lowest())] {
+            let interpretation = self.describe(&evaluation.interpretation);
+            writeln!(f, "{title}: {interpretation}: {}", evaluation.score)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Most choices a game may have for [`explore`], which scores a number of
+/// mappings that grows factorially with the choices.
+pub const MAX_EXPLORED_CHOICES: usize = 8;
+
+/// Scores `guide`, as parsed by [`parse_game_rounds`], under every mapping of
+/// the second column to my choices. If there are three letters, mappings to
+/// the desired outcomes are scored as well. Fails for games with more than
+/// [`MAX_EXPLORED_CHOICES`] choices.
+pub fn explore<'a>(game: &'a Game, guide: &[(Choice, Choice)]) -> Result<Exploration<'a>, Error> {
+    if game.len() > MAX_EXPLORED_CHOICES {
+        return Err(Error::Invalid(format!(
+            "Only games of at most {MAX_EXPLORED_CHOICES} choices can be explored, not {}",
+            game.len()
+        )));
+    }
+
+    let mut counts = vec![vec![0; game.len()]; game.len()];
+    for (opponent, letter) in guide {
+        counts[opponent.0][letter.0] += 1;
+    }
+
+    let total = |mine: &dyn Fn(Choice, usize) -> Choice| -> u32 {
+        game.choices()
+            .cartesian_product(0..game.len())
+            .map(|(opponent, letter)| {
+                counts[opponent.0][letter] * game.score_round((opponent, mine(opponent, letter)))
+            })
+            .sum()
+    };
+
+    let mut evaluations = Vec::new();
+    for choices in game.choices().permutations(game.len()) {
+        let score = total(&|_, letter| choices[letter]);
+        evaluations.push(Evaluation {
+            interpretation: Interpretation::Choices(choices),
+            score,
+        });
+    }
+
+    if game.len() == 3 {
+        let outcomes = [Outcome::Lose, Outcome::Draw, Outcome::Win];
+        for outcomes in outcomes.into_iter().permutations(3) {
+            let score =
+                total(&|opponent, letter| game.apply_strategy((opponent, outcomes[letter])).1);
+            evaluations.push(Evaluation {
+                interpretation: Interpretation::Outcomes(outcomes),
+                score,
+            });
+        }
+    }
+
+    Ok(Exploration { game, evaluations })
 }
 
 pub struct Solution;
@@ -133,25 +411,29 @@ impl crate::Solution for Solution {
     type O1 = u32;
     type O2 = u32;
 
-    fn part_one(input: &str) -> Self::O1 {
-        let (_, rounds) = parse_rounds(input).unwrap();
-        rounds.iter().copied().map(score_round).sum()
+    fn part_one(input: &str) -> Result<Self::O1, Error> {
+        let game = Game::rock_paper_scissors();
+        let rounds = parse_all(input, parse_game_rounds(&game))?;
+        Ok(rounds.iter().map(|round| game.score_round(*round)).sum())
     }
 
-    fn part_two(input: &str) -> Self::O2 {
-        let (_, rounds) = parse_desired_outcomes(input).unwrap();
-        rounds
+    fn part_two(input: &str) -> Result<Self::O2, Error> {
+        let game = Game::rock_paper_scissors();
+        let rounds = parse_all(input, parse_game_desired_outcomes(&game))?;
+        Ok(rounds
             .iter()
-            .copied()
-            .map(apply_strategy)
-            .map(score_round)
-            .sum()
+            .map(|round| game.score_round(game.apply_strategy(*round)))
+            .sum())
     }
 }
+
 #[cfg(test)]
 mod test {
-    use super::{Choice, Outcome};
+    use super::{Choice, Evaluation, Game, Interpretation, Outcome, OutcomeScores};
     use indoc::indoc;
+    use proptest::prelude::*;
+
+    use crate::Error;
 
     #[test]
     fn parse_rounds() {
@@ -165,13 +447,27 @@ mod test {
         assert_eq!(
             rounds,
             vec![
-                (Choice::Rock, Choice::Paper),
-                (Choice::Paper, Choice::Rock),
-                (Choice::Scissors, Choice::Scissors),
+                (Choice::ROCK, Choice::PAPER),
+                (Choice::PAPER, Choice::ROCK),
+                (Choice::SCISSORS, Choice::SCISSORS),
             ]
         );
     }
 
+    #[test]
+    fn parse_game_rounds() {
+        let game = Game::rock_paper_scissors_lizard_spock();
+        let input = indoc! {"
+            A V
+            E Z
+        "};
+
+        let spock = game.choice(4).unwrap();
+        let (_, rounds) = super::parse_game_rounds(&game)(input).unwrap();
+        assert_eq!(rounds, vec![(Choice::ROCK, Choice::ROCK), (spock, spock)]);
+        assert!(super::parse_rounds("A V\n").is_err());
+    }
+
     #[test]
     fn parse_desired_outcomes() {
         let input = indoc! {"
@@ -184,33 +480,157 @@ mod test {
         assert_eq!(
             rounds,
             vec![
-                (Choice::Rock, Outcome::Draw),
-                (Choice::Paper, Outcome::Lose),
-                (Choice::Scissors, Outcome::Win),
+                (Choice::ROCK, Outcome::Draw),
+                (Choice::PAPER, Outcome::Lose),
+                (Choice::SCISSORS, Outcome::Win),
             ]
         );
     }
 
     #[test]
     fn score_round() {
-        assert_eq!(super::score_round((Choice::Rock, Choice::Paper)), 8);
-        assert_eq!(super::score_round((Choice::Paper, Choice::Rock)), 1);
-        assert_eq!(super::score_round((Choice::Scissors, Choice::Scissors)), 6);
+        let game = Game::rock_paper_scissors();
+        assert_eq!(game.score_round((Choice::ROCK, Choice::PAPER)), 8);
+        assert_eq!(game.score_round((Choice::PAPER, Choice::ROCK)), 1);
+        assert_eq!(game.score_round((Choice::SCISSORS, Choice::SCISSORS)), 6);
+
+        let game = Game::rock_paper_scissors_lizard_spock();
+        let (lizard, spock) = (game.choice(3).unwrap(), game.choice(4).unwrap());
+        assert_eq!(game.score_round((spock, lizard)), 10);
+        assert_eq!(game.score_round((lizard, Choice::ROCK)), 7);
+        assert_eq!(Game::rock_paper_scissors().choice(3), None);
     }
 
     #[test]
     fn apply_strategy() {
+        let game = Game::rock_paper_scissors();
+        assert_eq!(
+            game.apply_strategy((Choice::ROCK, Outcome::Win)),
+            (Choice::ROCK, Choice::PAPER)
+        );
+        assert_eq!(
+            game.apply_strategy((Choice::PAPER, Outcome::Draw)),
+            (Choice::PAPER, Choice::PAPER)
+        );
+        assert_eq!(
+            game.apply_strategy((Choice::SCISSORS, Outcome::Lose)),
+            (Choice::SCISSORS, Choice::PAPER)
+        );
+
+        // Paper and Spock both beat rock, Spock scores more.
+        let game = Game::rock_paper_scissors_lizard_spock();
+        assert_eq!(
+            game.apply_strategy((Choice::ROCK, Outcome::Win)),
+            (Choice::ROCK, game.choice(4).unwrap())
+        );
+    }
+
+    #[test]
+    fn invalid_rules() {
+        let scores = OutcomeScores {
+            lose: 0,
+            draw: 3,
+            win: 6,
+        };
+        let choices = [("Rock", 1), ("Paper", 2), ("Scissors", 3)];
+
+        assert!(Game::new(&choices[..1], &[], scores).is_err());
+        assert!(Game::new(&choices[..2], &[(0, 1)], scores).is_err());
+        assert!(Game::new(&choices, &[(0, 2), (1, 0)], scores).is_err());
+        assert!(Game::new(&choices, &[(0, 2), (1, 0), (2, 1), (1, 2)], scores).is_err());
+        assert!(Game::new(&choices, &[(0, 2), (1, 0), (2, 3)], scores).is_err());
+    }
+
+    #[test]
+    fn explore() {
+        let game = Game::rock_paper_scissors();
+        let (_, guide) = super::parse_rounds("A Y\nB X\nC Z\n").unwrap();
+        let exploration = super::explore(&game, &guide).unwrap();
+
+        assert_eq!(exploration.evaluations.len(), 12);
         assert_eq!(
-            super::apply_strategy((Choice::Rock, Outcome::Win)),
-            (Choice::Rock, Choice::Paper)
+            exploration.evaluations[0],
+            Evaluation {
+                interpretation: Interpretation::Choices(vec![
+                    Choice::ROCK,
+                    Choice::PAPER,
+                    Choice::SCISSORS
+                ]),
+                score: 15,
+            }
         );
         assert_eq!(
-            super::apply_strategy((Choice::Paper, Outcome::Draw)),
-            (Choice::Paper, Choice::Paper)
+            exploration.evaluations[6],
+            Evaluation {
+                interpretation: Interpretation::Outcomes(vec![
+                    Outcome::Lose,
+                    Outcome::Draw,
+                    Outcome::Win
+                ]),
+                score: 12,
+            }
         );
+        assert_eq!(exploration.highest().score, 24);
+        assert_eq!(exploration.lowest().score, 6);
+
+        let report = exploration.to_string();
+        assert!(report.contains("Highest: X=Scissors Y=Paper Z=Rock: 24\n"));
+        assert!(report.contains("Lowest: X=Rock Y=Scissors Z=Paper: 6\n"));
+
+        let names = ["A", "B", "C", "D", "E", "F", "G", "H", "I"];
+        let choices: Vec<_> = names.iter().map(|name| (*name, 1)).collect();
+        let beats: Vec<_> = (0..9)
+            .flat_map(|winner| (1..=4).map(move |offset| (winner, (winner + offset) % 9)))
+            .collect();
+        let game = Game::new(&choices, &beats, Game::OUTCOME_SCORES).unwrap();
         assert_eq!(
-            super::apply_strategy((Choice::Scissors, Outcome::Lose)),
-            (Choice::Scissors, Choice::Paper)
+            super::explore(&game, &[]).err(),
+            Some(Error::Invalid(
+                "Only games of at most 8 choices can be explored, not 9".to_string()
+            ))
         );
     }
+
+    fn games() -> impl Strategy<Value = Game> {
+        prop_oneof![
+            Just(Game::rock_paper_scissors()),
+            Just(Game::rock_paper_scissors_lizard_spock()),
+        ]
+    }
+
+    fn reverse(outcome: Outcome) -> Outcome {
+        match outcome {
+            Outcome::Win => Outcome::Lose,
+            Outcome::Lose => Outcome::Win,
+            Outcome::Draw => Outcome::Draw,
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn outcome_is_antisymmetric(game in games(), a in 0..5usize, b in 0..5usize) {
+            let (a, b) = (Choice(a % game.len()), Choice(b % game.len()));
+            prop_assert_eq!(game.outcome(a, b), reverse(game.outcome(b, a)));
+            prop_assert_eq!(game.outcome(a, b) == Outcome::Draw, a == b);
+        }
+
+        #[test]
+        fn outcomes_are_balanced(game in games(), a in 0..5usize) {
+            let a = Choice(a % game.len());
+            let beaten = game.choices().filter(|b| game.outcome(a, *b) == Outcome::Win).count();
+            let beats = game.choices().filter(|b| game.outcome(a, *b) == Outcome::Lose).count();
+            prop_assert_eq!((beaten, beats), (game.len() / 2, game.len() / 2));
+        }
+
+        #[test]
+        fn strategy_reaches_outcome(
+            game in games(),
+            opponent in 0..5usize,
+            outcome in prop::sample::select(&[Outcome::Win, Outcome::Lose, Outcome::Draw][..]),
+        ) {
+            let opponent = Choice(opponent % game.len());
+            let (_, mine) = game.apply_strategy((opponent, outcome));
+            prop_assert_eq!(game.outcome(mine, opponent), outcome);
+        }
+    }
 }
@@ -0,0 +1,232 @@
+//! Plays a strategy guide against opponents that do not play as the guide
+//! expects.
+
+use std::fmt::{self, Display};
+
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+use super::{Choice, Game, Outcome};
+
+/// How the opponent picks its choice in every round.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Opponent {
+    /// Plays what the guide expects.
+    AsGuided,
+    /// Always plays the same choice.
+    Fixed(Choice),
+    /// Picks any choice with equal probability.
+    Random,
+    /// Counters my most frequent choice so far, picking randomly between
+    /// equally good counters.
+    CountsFrequency,
+    /// Plays my previous choice.
+    RepeatsLastMove,
+}
+
+impl Opponent {
+    /// Every opponent model for `game`, with one fixed opponent per choice.
+    pub fn all(game: &Game) -> Vec<Self> {
+        let mut opponents = vec![Self::AsGuided];
+        opponents.extend(game.choices().map(Self::Fixed));
+        opponents.extend([Self::Random, Self::CountsFrequency, Self::RepeatsLastMove]);
+        opponents
+    }
+
+    pub fn describe(&self, game: &Game) -> String {
+        match self {
+            Self::AsGuided => "as guided".to_string(),
+            Self::Fixed(choice) => format!("always {}", game.name(*choice)),
+            Self::Random => "random".to_string(),
+            Self::CountsFrequency => "counts frequency".to_string(),
+            Self::RepeatsLastMove => "repeats last move".to_string(),
+        }
+    }
+}
+
+/// Plays one game of `plan`, pairs of the opponent's expected choice and my
+/// choice, against `opponent`. Returns my total score.
+pub fn play(game: &Game, plan: &[(Choice, Choice)], opponent: Opponent, rng: &mut StdRng) -> u32 {
+    let mut counts = vec![0; game.len()];
+    let mut last = None;
+
+    plan.iter()
+        .map(|&(expected, mine)| {
+            let theirs = match opponent {
+                Opponent::AsGuided => expected,
+                Opponent::Fixed(choice) => choice,
+                Opponent::Random => Choice(rng.gen_range(0..game.len())),
+                Opponent::CountsFrequency => {
+                    counter_most_frequent(game, &counts, rng).unwrap_or(expected)
+                }
+                Opponent::RepeatsLastMove => last.unwrap_or(expected),
+            };
+
+            counts[mine.0] += 1;
+            last = Some(mine);
+            game.score_round((theirs, mine))
+        })
+        .sum()
+}
+
+/// A choice beating the choice played the most according to `counts`, or
+/// `None` before anything was played.
+fn counter_most_frequent(game: &Game, counts: &[usize], rng: &mut StdRng) -> Option<Choice> {
+    let most = counts.iter().copied().max().filter(|most| *most > 0)?;
+    let frequent: Vec<Choice> = game.choices().filter(|c| counts[c.0] == most).collect();
+    let target = *frequent.choose(rng)?;
+
+    let counters: Vec<Choice> = game
+        .choices()
+        .filter(|c| game.outcome(*c, target) == Outcome::Win)
+        .collect();
+    counters.choose(rng).copied()
+}
+
+/// Scores of many games against the same opponent, sorted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Distribution {
+    scores: Vec<u32>,
+}
+
+impl Distribution {
+    pub fn scores(&self) -> &[u32] {
+        &self.scores
+    }
+
+    pub fn min(&self) -> u32 {
+        self.scores.first().copied().unwrap_or(0)
+    }
+
+    pub fn max(&self) -> u32 {
+        self.scores.last().copied().unwrap_or(0)
+    }
+
+    pub fn mean(&self) -> f64 {
+        let total: u64 = self.scores.iter().copied().map(u64::from).sum();
+        total as f64 / self.scores.len().max(1) as f64
+    }
+
+    /// Nearest-rank percentile.
+    pub fn percentile(&self, percentile: u8) -> u32 {
+        let rank = (usize::from(percentile) * self.scores.len()).div_ceil(100);
+        self.scores.get(rank.max(1) - 1).copied().unwrap_or(0)
+    }
+}
+
+/// Plays `games` games, the n-th one seeded with `seed + n`, wrapping around.
+pub fn simulate(
+    game: &Game,
+    plan: &[(Choice, Choice)],
+    opponent: Opponent,
+    games: usize,
+    seed: u64,
+) -> Distribution {
+    let mut scores: Vec<u32> = (0..games as u64)
+        .map(|n| {
+            play(
+                game,
+                plan,
+                opponent,
+                &mut StdRng::seed_from_u64(seed.wrapping_add(n)),
+            )
+        })
+        .collect();
+    scores.sort_unstable();
+
+    Distribution { scores }
+}
+
+/// Score distributions of following a guide against every opponent model.
+pub struct Report<'a> {
+    game: &'a Game,
+    /// Distributions per way of reading the guide and opponent.
+    pub rows: Vec<(&'static str, Opponent, Distribution)>,
+}
+
+/// Simulates every opponent model against each of the `plans`, named by how
+/// the guide was read to build them.
+pub fn report<'a>(
+    game: &'a Game,
+    plans: &[(&'static str, Vec<(Choice, Choice)>)],
+    games: usize,
+    seed: u64,
+) -> Report<'a> {
+    let rows = plans
+        .iter()
+        .flat_map(|(reading, plan)| {
+            Opponent::all(game).into_iter().map(move |opponent| {
+                let distribution = simulate(game, plan, opponent, games, seed);
+                (*reading, opponent, distribution)
+            })
+        })
+        .collect();
+
+    Report { game, rows }
+}
+
+impl Display for Report<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<10} {:<18} {:>7} {:>9} {:>7} {:>7} {:>7}",
+            "Reading", "Opponent", "Min", "Mean", "Median", "p90", "Max"
+        )?;
+        for (reading, opponent, distribution) in &self.rows {
+            writeln!(
+                f,
+                "{reading:<10} {:<18} {:>7} {:>9.1} {:>7} {:>7} {:>7}",
+                opponent.describe(self.game),
+                distribution.min(),
+                distribution.mean(),
+                distribution.percentile(50),
+                distribution.percentile(90),
+                distribution.max()
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::{Game, Opponent};
+    use crate::day_two::{parse_rounds, Choice};
+
+    fn plan() -> Vec<(Choice, Choice)> {
+        parse_rounds("A Y\nB X\nC Z\n").unwrap().1
+    }
+
+    #[test]
+    fn play() {
+        let game = Game::rock_paper_scissors();
+        let play = |opponent| super::play(&game, &plan(), opponent, &mut StdRng::seed_from_u64(0));
+
+        assert_eq!(play(Opponent::AsGuided), 15);
+        assert_eq!(play(Opponent::Fixed(Choice::ROCK)), 15);
+        assert_eq!(play(Opponent::RepeatsLastMove), 12);
+        assert!([21, 24].contains(&play(Opponent::CountsFrequency)));
+    }
+
+    #[test]
+    fn simulate() {
+        let game = Game::rock_paper_scissors();
+        let random = super::simulate(&game, &plan(), Opponent::Random, 100, 7);
+
+        assert_eq!(
+            random,
+            super::simulate(&game, &plan(), Opponent::Random, 100, 7)
+        );
+        assert_eq!(random.scores().len(), 100);
+        assert!(random.min() < random.max());
+        assert!(random.min() <= random.percentile(50) && random.percentile(50) <= random.max());
+
+        let wrapping = super::simulate(&game, &plan(), Opponent::Random, 2, u64::MAX);
+        assert_eq!(wrapping.scores().len(), 2);
+
+        let guided = super::simulate(&game, &plan(), Opponent::AsGuided, 10, 7);
+        assert_eq!((guided.min(), guided.max(), guided.mean()), (15, 15, 15.0));
+    }
+}
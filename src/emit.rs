@@ -0,0 +1,57 @@
+//! Emits the parsed model of a day's input for use by other tools.
+
+use nom::multi::many0;
+use serde::Serialize;
+use serde_json::json;
+
+use crate::{
+    day_eight, day_eleven, day_five, day_four, day_one, day_seven, day_ten, day_two,
+    error::parse_all, Error,
+};
+
+fn to_json(model: &impl Serialize) -> Result<String, Error> {
+    serde_json::to_string_pretty(model).map_err(|error| Error::Invalid(error.to_string()))
+}
+
+/// The parsed model of `input` for `day` as pretty-printed JSON, or `None` if
+/// the day has no structured model.
+pub fn json(day: usize, input: &str) -> Result<Option<String>, Error> {
+    let json = match day {
+        1 => to_json(&day_one::parse(input)?),
+        2 => to_json(&json!({
+            "rounds": parse_all(input, day_two::parse_rounds)?,
+            "desired_outcomes": parse_all(input, day_two::parse_desired_outcomes)?,
+        })),
+        4 => to_json(&parse_all(input, day_four::parse)?),
+        5 => {
+            let (stacks, procedure) = parse_all(input, day_five::parse)?;
+            to_json(&json!({ "stacks": stacks, "procedure": procedure }))
+        }
+        7 => to_json(&parse_all(input, day_seven::parse_directory)?),
+        8 => to_json(&day_eight::parse(input)?),
+        10 => to_json(&parse_all(input, many0(day_ten::parse_instruction))?),
+        11 => to_json(&parse_all(input, day_eleven::parse)?),
+        _ => return Ok(None),
+    };
+
+    json.map(Some)
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    #[test]
+    fn json() {
+        let input = indoc! {"
+            1-3,2-7
+        "};
+        let json = super::json(4, input).unwrap().unwrap();
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&json).unwrap(),
+            serde_json::json!([[{ "start": 1, "end": 3 }, { "start": 2, "end": 7 }]])
+        );
+
+        assert_eq!(super::json(6, "abcd"), Ok(None));
+    }
+}
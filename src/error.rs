@@ -0,0 +1,90 @@
+use std::fmt::{self, Display};
+
+use nom::IResult;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The input does not follow the puzzle's format.
+    Parse(String),
+    /// The input is well-formed, but the puzzle it describes has no answer.
+    Invalid(String),
+}
+
+impl Error {
+    /// Error for the unparsed `rest` of `input`, pointing at the line it starts on.
+    pub(crate) fn unexpected(input: &str, rest: &str) -> Self {
+        let offset = input.len() - rest.len();
+        let line = input[..offset].matches('\n').count() + 1;
+        let found: String = rest
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .chars()
+            .take(40)
+            .collect();
+
+        if rest.is_empty() {
+            Self::Parse(format!("line {line}: unexpected end of input"))
+        } else {
+            Self::Parse(format!("line {line}: unexpected input `{found}`"))
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse(message) => write!(f, "Failed to parse input: {message}"),
+            Error::Invalid(message) => write!(f, "Invalid puzzle: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Runs `parser` over `input`, failing unless everything but trailing
+/// whitespace is consumed.
+pub(crate) fn parse_all<'a, O>(
+    input: &'a str,
+    mut parser: impl FnMut(&'a str) -> IResult<&'a str, O>,
+) -> Result<O, Error> {
+    complete(input, parser(input))
+}
+
+/// Converts the `result` of parsing `input`, failing unless everything but
+/// trailing whitespace was consumed.
+pub(crate) fn complete<'a, O>(input: &'a str, result: IResult<&'a str, O>) -> Result<O, Error> {
+    match result {
+        Ok((rest, output)) if rest.trim_start().is_empty() => Ok(output),
+        Ok((rest, _)) => Err(Error::unexpected(input, rest.trim_start())),
+        Err(nom::Err::Error(error) | nom::Err::Failure(error)) => {
+            Err(Error::unexpected(input, error.input))
+        }
+        Err(nom::Err::Incomplete(_)) => Err(Error::unexpected(input, "")),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use nom::{
+        character::complete::{newline, u32 as parse_u32},
+        multi::separated_list1,
+    };
+
+    use super::Error;
+
+    #[test]
+    fn parse_all() {
+        let numbers = |input| super::parse_all(input, separated_list1(newline, parse_u32));
+
+        assert_eq!(numbers("1\n2\n"), Ok(vec![1, 2]));
+        assert_eq!(
+            numbers("1\n2\nthree\n"),
+            Err(Error::Parse("line 3: unexpected input `three`".to_string()))
+        );
+        assert_eq!(
+            numbers(""),
+            Err(Error::Parse("line 1: unexpected end of input".to_string()))
+        );
+    }
+}
@@ -0,0 +1,56 @@
+//! Normalization of puzzle inputs, so inputs saved on any platform parse the
+//! same way.
+
+use alloc::{borrow::Cow, string::String};
+
+const BOM: char = '\u{feff}';
+
+/// Removes a leading byte order mark, turns CRLF line endings into LF, strips
+/// trailing whitespace from every line and ends the input with exactly one
+/// newline. Empty and whitespace-only inputs become empty.
+///
+/// Leading whitespace is kept. Days whose input is aligned with trailing
+/// spaces, like the crate drawing of day 5, must not be normalized.
+pub fn normalize(input: &str) -> Cow<'_, str> {
+    let input = input.strip_prefix(BOM).unwrap_or(input);
+    if is_normalized(input) {
+        return Cow::Borrowed(input);
+    }
+
+    let mut normalized = String::with_capacity(input.len());
+    for line in input.trim_end().lines() {
+        normalized.push_str(line.trim_end());
+        normalized.push('\n');
+    }
+
+    Cow::Owned(normalized)
+}
+
+fn is_normalized(input: &str) -> bool {
+    input.is_empty()
+        || (input.ends_with('\n')
+            && !input.ends_with("\n\n")
+            && input.split('\n').all(|line| line.trim_end() == line))
+}
+
+#[cfg(test)]
+mod test {
+    use std::borrow::Cow;
+
+    #[test]
+    fn normalized_input_is_borrowed() {
+        assert!(matches!(
+            super::normalize("1\n\n2\n"),
+            Cow::Borrowed("1\n\n2\n")
+        ));
+        assert!(matches!(super::normalize(""), Cow::Borrowed("")));
+    }
+
+    #[test]
+    fn normalize() {
+        assert_eq!(super::normalize("\u{feff}1\r\n\r\n2\r\n"), "1\n\n2\n");
+        assert_eq!(super::normalize("1  \n \n  2\t"), "1\n\n  2\n");
+        assert_eq!(super::normalize("1\n2\n\n\n"), "1\n2\n");
+        assert_eq!(super::normalize(" \r\n\n"), "");
+    }
+}
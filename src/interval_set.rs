@@ -0,0 +1,320 @@
+use alloc::vec::Vec;
+use core::ops::RangeInclusive;
+
+/// Integer types an [`IntervalSet`] can hold.
+pub trait Integer: Copy + Ord {
+    /// The next integer, or `None` at the maximum.
+    fn successor(self) -> Option<Self>;
+
+    /// The previous integer, or `None` at the minimum.
+    fn predecessor(self) -> Option<Self>;
+
+    /// Number of integers in `start..=end`, saturating at `u128::MAX`.
+    fn count(start: Self, end: Self) -> u128;
+}
+
+macro_rules! impl_integer {
+    ($($t:ty),*) => {$(
+        impl Integer for $t {
+            fn successor(self) -> Option<Self> {
+                self.checked_add(1)
+            }
+
+            fn predecessor(self) -> Option<Self> {
+                self.checked_sub(1)
+            }
+
+            fn count(start: Self, end: Self) -> u128 {
+                // The difference fits in 128 bits for every integer type, even
+                // when the signed subtraction wraps.
+                ((end as i128).wrapping_sub(start as i128) as u128).saturating_add(1)
+            }
+        }
+    )*};
+}
+
+impl_integer!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// Whether an interval ending at `end` overlaps or is adjacent to one
+/// starting at `start`.
+fn touches<T: Integer>(end: T, start: T) -> bool {
+    start <= end || end.successor() == Some(start)
+}
+
+/// A set of integers, stored as sorted, disjoint and non-adjacent inclusive
+/// intervals.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IntervalSet<T> {
+    intervals: Vec<(T, T)>,
+}
+
+impl<T: Integer> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self {
+            intervals: Vec::new(),
+        }
+    }
+
+    /// Adds every integer of `range`, merging it with the intervals it
+    /// overlaps or is adjacent to.
+    pub fn insert(&mut self, range: RangeInclusive<T>) {
+        let (mut start, mut end) = range.into_inner();
+        if start > end {
+            return;
+        }
+
+        let first = self.intervals.partition_point(|&(_, e)| !touches(e, start));
+        let last = self.intervals.partition_point(|&(s, _)| touches(end, s));
+        if first < last {
+            start = start.min(self.intervals[first].0);
+            end = end.max(self.intervals[last - 1].1);
+        }
+
+        self.intervals.splice(first..last, [(start, end)]);
+    }
+
+    /// The intervals, in increasing order.
+    pub fn iter(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        self.intervals.iter().map(|&(start, end)| start..=end)
+    }
+
+    /// The intervals missing between the first and the last interval.
+    pub fn gaps(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        self.intervals.windows(2).map(|pair| {
+            let (_, end) = pair[0];
+            let (start, _) = pair[1];
+            // Intervals are never adjacent, so the gap is never empty.
+            end.successor().unwrap()..=start.predecessor().unwrap()
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Number of integers in the set, saturating at `u128::MAX`.
+    pub fn covered_len(&self) -> u128 {
+        self.intervals
+            .iter()
+            .map(|&(start, end)| T::count(start, end))
+            .fold(0, u128::saturating_add)
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let index = self.intervals.partition_point(|&(_, end)| end < value);
+        self.intervals
+            .get(index)
+            .is_some_and(|&(start, _)| start <= value)
+    }
+
+    /// Whether every integer of `range` is in the set.
+    pub fn contains_range(&self, range: &RangeInclusive<T>) -> bool {
+        let (start, end) = (*range.start(), *range.end());
+        if start > end {
+            return true;
+        }
+
+        let index = self.intervals.partition_point(|&(_, e)| e < start);
+        self.intervals
+            .get(index)
+            .is_some_and(|&(s, e)| s <= start && end <= e)
+    }
+
+    /// Whether every integer of `other` is in the set.
+    pub fn is_superset(&self, other: &Self) -> bool {
+        other.iter().all(|range| self.contains_range(&range))
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        union.extend(other.iter());
+        union
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intervals = Vec::new();
+        let (mut a, mut b) = (
+            self.intervals.iter().peekable(),
+            other.intervals.iter().peekable(),
+        );
+
+        while let (Some(&&(a_start, a_end)), Some(&&(b_start, b_end))) = (a.peek(), b.peek()) {
+            let start = a_start.max(b_start);
+            let end = a_end.min(b_end);
+            if start <= end {
+                intervals.push((start, end));
+            }
+
+            if a_end < b_end {
+                a.next();
+            } else {
+                b.next();
+            }
+        }
+
+        Self { intervals }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut intervals = Vec::new();
+        let mut removed = other.intervals.iter().peekable();
+
+        for &(start, end) in &self.intervals {
+            // Start of what is left of the interval, if anything.
+            let mut rest = Some(start);
+
+            while let (Some(start), Some(&&(r_start, r_end))) = (rest, removed.peek()) {
+                if r_end < start {
+                    removed.next();
+                    continue;
+                }
+                if r_start > end {
+                    break;
+                }
+
+                if r_start > start {
+                    // `r_start > start`, so it has a predecessor.
+                    intervals.push((start, r_start.predecessor().unwrap()));
+                }
+                if r_end >= end {
+                    // The removed interval may cover the next intervals too.
+                    rest = None;
+                } else {
+                    rest = r_end.successor();
+                    removed.next();
+                }
+            }
+
+            if let Some(start) = rest {
+                intervals.push((start, end));
+            }
+        }
+
+        Self { intervals }
+    }
+}
+
+impl<T: Integer> From<RangeInclusive<T>> for IntervalSet<T> {
+    fn from(range: RangeInclusive<T>) -> Self {
+        let mut set = Self::new();
+        set.insert(range);
+        set
+    }
+}
+
+impl<T: Integer> Extend<RangeInclusive<T>> for IntervalSet<T> {
+    fn extend<I: IntoIterator<Item = RangeInclusive<T>>>(&mut self, iter: I) {
+        for range in iter {
+            self.insert(range)
+        }
+    }
+}
+
+impl<T: Integer> FromIterator<RangeInclusive<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<T>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+
+    use proptest::prelude::*;
+
+    use super::IntervalSet;
+
+    fn ranges() -> impl Strategy<Value = Vec<(u8, u8)>> {
+        prop::collection::vec((any::<u8>(), any::<u8>()), 0..8)
+    }
+
+    fn set(ranges: &[(u8, u8)]) -> IntervalSet<u8> {
+        ranges.iter().map(|&(a, b)| a..=b).collect()
+    }
+
+    /// The same set, one integer at a time.
+    fn model(ranges: &[(u8, u8)]) -> BTreeSet<u8> {
+        ranges.iter().flat_map(|&(a, b)| a..=b).collect()
+    }
+
+    fn members(set: &IntervalSet<u8>) -> BTreeSet<u8> {
+        set.iter().flatten().collect()
+    }
+
+    #[test]
+    fn insert_merges() {
+        let set: IntervalSet<u32> = [1..=3, 10..=12, 4..=5, 8..=8].into_iter().collect();
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![1..=5, 8..=8, 10..=12]);
+        assert_eq!(set.gaps().collect::<Vec<_>>(), vec![6..=7, 9..=9]);
+        assert_eq!(set.covered_len(), 9);
+
+        let mut set = set;
+        set.insert(6..=9);
+        #[allow(clippy::reversed_empty_ranges)]
+        set.insert(7..=2);
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![1..=12]);
+    }
+
+    #[test]
+    fn queries() {
+        let set: IntervalSet<i8> = [-128..=-100, 0..=10, 120..=127].into_iter().collect();
+
+        assert!(set.contains(-128) && set.contains(127) && !set.contains(11));
+        assert!(set.contains_range(&(2..=8)) && !set.contains_range(&(8..=12)));
+        assert!(set.is_superset(&IntervalSet::from(1..=3)));
+        assert_eq!(set.covered_len(), 29 + 11 + 8);
+        assert_eq!(
+            IntervalSet::from(i128::MIN..=i128::MAX).covered_len(),
+            u128::MAX
+        );
+    }
+
+    #[test]
+    fn set_operations() {
+        let a: IntervalSet<u32> = [1..=10, 20..=30].into_iter().collect();
+        let b: IntervalSet<u32> = [5..=25].into_iter().collect();
+
+        assert_eq!(a.union(&b).iter().collect::<Vec<_>>(), vec![1..=30]);
+        assert_eq!(
+            a.intersection(&b).iter().collect::<Vec<_>>(),
+            vec![5..=10, 20..=25]
+        );
+        assert_eq!(
+            a.difference(&b).iter().collect::<Vec<_>>(),
+            vec![1..=4, 26..=30]
+        );
+        assert_eq!(b.difference(&a).iter().collect::<Vec<_>>(), vec![11..=19]);
+    }
+
+    proptest! {
+        #[test]
+        fn matches_model(a in ranges(), b in ranges(), value in any::<u8>()) {
+            let (set_a, set_b) = (set(&a), set(&b));
+            let (model_a, model_b) = (model(&a), model(&b));
+
+            prop_assert_eq!(members(&set_a), model_a.clone());
+            prop_assert_eq!(set_a.covered_len(), model_a.len() as u128);
+            prop_assert_eq!(set_a.contains(value), model_a.contains(&value));
+            prop_assert_eq!(set_a.is_superset(&set_b), model_a.is_superset(&model_b));
+
+            prop_assert_eq!(members(&set_a.union(&set_b)), &model_a | &model_b);
+            prop_assert_eq!(members(&set_a.intersection(&set_b)), &model_a & &model_b);
+            prop_assert_eq!(members(&set_a.difference(&set_b)), &model_a - &model_b);
+        }
+
+        #[test]
+        fn intervals_are_disjoint_and_gaps_fill_them(a in ranges()) {
+            let set = set(&a);
+            let intervals: Vec<_> = set.iter().collect();
+            for pair in intervals.windows(2) {
+                prop_assert!(u16::from(*pair[0].end()) + 1 < u16::from(*pair[1].start()));
+            }
+
+            let mut filled = set.clone();
+            filled.extend(set.gaps());
+            prop_assert!(filled.iter().count() <= 1);
+        }
+    }
+}
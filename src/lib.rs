@@ -0,0 +1,105 @@
+//! Solutions to Advent of Code 2022.
+//!
+//! Without the default `std` feature only the utilities are built, as a
+//! `no_std` library.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+use std::{collections::HashMap, fmt::Display};
+
+#[cfg(feature = "std")]
+pub mod day_eight;
+#[cfg(feature = "std")]
+pub mod day_eleven;
+#[cfg(feature = "std")]
+pub mod day_five;
+#[cfg(feature = "std")]
+pub mod day_four;
+#[cfg(feature = "std")]
+pub mod day_nine;
+#[cfg(feature = "std")]
+pub mod day_one;
+#[cfg(feature = "std")]
+pub mod day_seven;
+#[cfg(feature = "std")]
+pub mod day_six;
+#[cfg(feature = "std")]
+pub mod day_ten;
+#[cfg(feature = "std")]
+pub mod day_three;
+#[cfg(feature = "std")]
+pub mod day_two;
+
+pub mod input;
+pub mod interval_set;
+pub mod limit_heap;
+
+#[cfg(feature = "std")]
+pub mod analyze;
+#[cfg(feature = "serde")]
+pub mod emit;
+#[cfg(feature = "std")]
+mod error;
+#[cfg(feature = "std")]
+pub use error::Error;
+
+#[cfg(feature = "std")]
+pub trait SolutionRunner {
+    fn part_one(&self, input: &str) -> Result<String, Error>;
+    fn part_two(&self, input: &str) -> Result<String, Error>;
+    fn day(&self) -> usize;
+}
+
+#[cfg(feature = "std")]
+pub trait Solution {
+    const DAY: usize;
+    type O1: Display;
+    type O2: Display;
+
+    fn part_one(input: &str) -> Result<Self::O1, Error>;
+
+    fn part_two(input: &str) -> Result<Self::O2, Error>;
+}
+
+#[cfg(feature = "std")]
+impl<T: Solution> SolutionRunner for T {
+    fn part_one(&self, input: &str) -> Result<String, Error> {
+        T::part_one(input).map(|answer| answer.to_string())
+    }
+
+    fn part_two(&self, input: &str) -> Result<String, Error> {
+        T::part_two(input).map(|answer| answer.to_string())
+    }
+
+    fn day(&self) -> usize {
+        T::DAY
+    }
+}
+
+#[cfg(feature = "std")]
+fn create_solution_map<const N: usize>(
+    solutions: [&'static dyn SolutionRunner; N],
+) -> HashMap<usize, &'static dyn SolutionRunner> {
+    solutions.into_iter().map(|s| (s.day(), s)).collect()
+}
+
+#[cfg(feature = "std")]
+/// Every registered solution, keyed by day.
+pub fn solutions() -> HashMap<usize, &'static dyn SolutionRunner> {
+    create_solution_map([
+        &day_one::Solution,
+        &day_two::Solution,
+        &day_three::Solution,
+        &day_four::Solution,
+        &day_five::Solution,
+        &day_six::Solution,
+        &day_seven::Solution,
+        &day_eight::Solution,
+        &day_nine::Solution,
+        &day_ten::Solution,
+        &day_eleven::Solution,
+    ])
+}
@@ -1,49 +1,456 @@
-use std::{cmp::Reverse, collections::BinaryHeap, iter::Map};
+use alloc::vec::Vec;
+use core::cmp::Ordering;
 
-pub struct LimitHeap<T: Ord, const N: usize>(BinaryHeap<Reverse<T>>);
+/// Decides which items a [`LimitHeap`] or [`ArrayLimitHeap`] keeps: the
+/// greatest ones according to [`Compare::compare`].
+pub trait Compare<T> {
+    fn compare(&self, a: &T, b: &T) -> Ordering;
+}
+
+/// Keeps the largest items.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Largest;
+
+impl<T: Ord> Compare<T> for Largest {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        a.cmp(b)
+    }
+}
+
+/// Keeps the smallest items.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Smallest;
+
+impl<T: Ord> Compare<T> for Smallest {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        b.cmp(a)
+    }
+}
+
+/// Keeps the items with the largest key.
+#[derive(Clone, Copy, Debug)]
+pub struct ByKey<F>(pub F);
+
+impl<T, K: Ord, F: Fn(&T) -> K> Compare<T> for ByKey<F> {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        (self.0)(a).cmp(&(self.0)(b))
+    }
+}
+
+/// Keeps the greatest items according to a comparator closure.
+#[derive(Clone, Copy, Debug)]
+pub struct By<F>(pub F);
+
+impl<T, F: Fn(&T, &T) -> Ordering> Compare<T> for By<F> {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        (self.0)(a, b)
+    }
+}
+
+/// Moves the last item of the binary heap `items` up to its place.
+fn sift_up<T>(items: &mut [T], compare: &impl Compare<T>) {
+    let mut index = items.len() - 1;
+    while index > 0 {
+        let parent = (index - 1) / 2;
+        if compare.compare(&items[index], &items[parent]) != Ordering::Less {
+            break;
+        }
+        items.swap(index, parent);
+        index = parent;
+    }
+}
+
+/// Moves the root of the binary heap `items` down to its place.
+fn sift_down<T>(items: &mut [T], compare: &impl Compare<T>) {
+    let less = |a: &T, b: &T| compare.compare(a, b) == Ordering::Less;
+
+    let mut index = 0;
+    loop {
+        let left = 2 * index + 1;
+        let right = left + 1;
+        if left >= items.len() {
+            break;
+        }
+
+        let least_child = if right < items.len() && less(&items[right], &items[left]) {
+            right
+        } else {
+            left
+        };
+        if !less(&items[least_child], &items[index]) {
+            break;
+        }
+        items.swap(index, least_child);
+        index = least_child;
+    }
+}
+
+/// Keeps the largest items pushed into it, up to a capacity. See
+/// [`ArrayLimitHeap`] for a version that never allocates.
+///
+/// Heaps created with [`LimitHeap::new`], [`Default`] or by collecting hold
+/// `N` items. Heaps created with [`LimitHeap::with_capacity`] and friends
+/// leave `N` at 0 and pick their capacity at runtime instead, so `N` must not
+/// be 0 for the others, which would keep nothing:
+///
+/// ```compile_fail
+/// # use aoc_2022::limit_heap::LimitHeap;
+/// let heap: LimitHeap<u32> = [1, 2, 3].into_iter().collect();
+/// ```
+///
+/// What counts as largest is decided by `C`, see [`LimitHeap::smallest`],
+/// [`LimitHeap::by_key`] and [`LimitHeap::by`].
+pub struct LimitHeap<T, const N: usize = 0, C = Largest> {
+    /// Binary heap with the least item according to `compare` at the root.
+    items: Vec<T>,
+    capacity: usize,
+    compare: C,
+}
+
+impl<T: Ord> LimitHeap<T> {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_comparator(capacity, Largest)
+    }
+}
+
+impl<T: Ord> LimitHeap<T, 0, Smallest> {
+    /// Keeps the `capacity` smallest items.
+    pub fn smallest(capacity: usize) -> Self {
+        Self::with_comparator(capacity, Smallest)
+    }
+}
+
+impl<T, K: Ord, F: Fn(&T) -> K> LimitHeap<T, 0, ByKey<F>> {
+    /// Keeps the `capacity` items with the largest `key`.
+    pub fn by_key(capacity: usize, key: F) -> Self {
+        Self::with_comparator(capacity, ByKey(key))
+    }
+}
+
+impl<T, F: Fn(&T, &T) -> Ordering> LimitHeap<T, 0, By<F>> {
+    /// Keeps the `capacity` greatest items according to `compare`.
+    pub fn by(capacity: usize, compare: F) -> Self {
+        Self::with_comparator(capacity, By(compare))
+    }
+}
+
+impl<T, C: Compare<T>> LimitHeap<T, 0, C> {
+    pub fn with_comparator(capacity: usize, compare: C) -> Self {
+        Self {
+            items: Vec::with_capacity(capacity),
+            capacity,
+            compare,
+        }
+    }
+}
+
+impl<T, const N: usize, C> LimitHeap<T, N, C> {
+    /// The capacity of heaps built without a runtime capacity, failing to
+    /// compile when it is 0.
+    const CAPACITY: usize = {
+        assert!(N > 0, "a LimitHeap of capacity 0 needs a runtime capacity");
+        N
+    };
+}
 
 impl<T: Ord, const N: usize> LimitHeap<T, N> {
     pub fn new() -> Self {
-        Self(BinaryHeap::new())
+        Self::default()
     }
+}
 
+impl<T, const N: usize, C: Compare<T>> LimitHeap<T, N, C> {
     pub fn push(&mut self, item: T) {
-        if self.0.len() < N {
-            self.0.push(Reverse(item));
+        if self.items.len() < self.capacity {
+            self.items.push(item);
+            sift_up(&mut self.items, &self.compare);
             return;
         }
 
-        match self.0.peek() {
-            Some(Reverse(smallest_value)) if smallest_value < &item => {
-                self.0.pop();
-                self.0.push(Reverse(item))
+        match self.items.first() {
+            Some(least) if self.compare.compare(least, &item) == Ordering::Less => {
+                self.items[0] = item;
+                sift_down(&mut self.items, &self.compare);
             }
             _ => {}
         }
     }
 
+    /// Iterates over the kept items in arbitrary order.
     pub fn iter(&self) -> impl Iterator<Item = &T> {
-        self.0.iter().map(|reversed_item| &reversed_item.0)
+        self.items.iter()
+    }
+
+    /// The kept item the comparator ranks lowest, which is the next one to be
+    /// evicted: the smallest under [`Largest`], the largest under
+    /// [`Smallest`].
+    pub fn peek_next_evicted(&self) -> Option<&T> {
+        self.items.first()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.items.len() >= self.capacity
+    }
+
+    /// Adds the items kept by `other`, as if they had been pushed into this
+    /// heap.
+    pub fn merge(&mut self, other: Self) {
+        self.extend(other.items)
+    }
+
+    /// The kept items, greatest first.
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        let compare = &self.compare;
+        self.items.sort_by(|a, b| compare.compare(b, a));
+        self.items
+    }
+}
+
+impl<T, const N: usize, C: Compare<T> + Default> Default for LimitHeap<T, N, C> {
+    fn default() -> Self {
+        Self {
+            items: Vec::with_capacity(Self::CAPACITY),
+            capacity: Self::CAPACITY,
+            compare: C::default(),
+        }
+    }
+}
+
+impl<T, const N: usize, C: Compare<T>> Extend<T> for LimitHeap<T, N, C> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push(item)
+        }
     }
 }
 
-impl<A: Ord, const N: usize> FromIterator<A> for LimitHeap<A, N> {
+impl<A, const N: usize, C: Compare<A> + Default> FromIterator<A> for LimitHeap<A, N, C> {
     fn from_iter<T: IntoIterator<Item = A>>(iter: T) -> Self {
-        let mut heap = Self::new();
+        let mut heap = Self::default();
+        heap.extend(iter);
+        heap
+    }
+}
+
+/// Collects in parallel, merging the heaps built on each thread. Like
+/// [`FromIterator`], this keeps `N` items, and `N` must not be 0.
+#[cfg(feature = "rayon")]
+impl<A, const N: usize, C> rayon::iter::FromParallelIterator<A> for LimitHeap<A, N, C>
+where
+    A: Send,
+    C: Compare<A> + Default + Send,
+{
+    fn from_par_iter<I>(par_iter: I) -> Self
+    where
+        I: rayon::iter::IntoParallelIterator<Item = A>,
+    {
+        use rayon::iter::ParallelIterator;
+
+        par_iter
+            .into_par_iter()
+            .fold(Self::default, |mut heap, item| {
+                heap.push(item);
+                heap
+            })
+            .reduce(Self::default, |mut heap, other| {
+                heap.merge(other);
+                heap
+            })
+    }
+}
+
+/// Yields the kept items, greatest first.
+impl<T, const N: usize, C: Compare<T>> IntoIterator for LimitHeap<T, N, C> {
+    type Item = T;
+    type IntoIter = alloc::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.into_sorted_vec().into_iter()
+    }
+}
+
+/// A [`LimitHeap`] that keeps its `N` items inline instead of on the heap.
+pub struct ArrayLimitHeap<T, const N: usize, C = Largest> {
+    /// Binary heap with the least item according to `compare` at the root.
+    items: heapless::Vec<T, N>,
+    compare: C,
+}
+
+impl<T: Ord, const N: usize> ArrayLimitHeap<T, N> {
+    pub fn new() -> Self {
+        Self::with_comparator(Largest)
+    }
+}
+
+impl<T: Ord, const N: usize> ArrayLimitHeap<T, N, Smallest> {
+    /// Keeps the `N` smallest items.
+    pub fn smallest() -> Self {
+        Self::with_comparator(Smallest)
+    }
+}
+
+impl<T, K: Ord, F: Fn(&T) -> K, const N: usize> ArrayLimitHeap<T, N, ByKey<F>> {
+    /// Keeps the `N` items with the largest `key`.
+    pub fn by_key(key: F) -> Self {
+        Self::with_comparator(ByKey(key))
+    }
+}
 
+impl<T, F: Fn(&T, &T) -> Ordering, const N: usize> ArrayLimitHeap<T, N, By<F>> {
+    /// Keeps the `N` greatest items according to `compare`.
+    pub fn by(compare: F) -> Self {
+        Self::with_comparator(By(compare))
+    }
+}
+
+impl<T, const N: usize, C: Compare<T>> ArrayLimitHeap<T, N, C> {
+    pub fn with_comparator(compare: C) -> Self {
+        Self {
+            items: heapless::Vec::new(),
+            compare,
+        }
+    }
+
+    pub fn push(&mut self, item: T) {
+        if !self.is_full() {
+            // Cannot fail, there is room left.
+            let _ = self.items.push(item);
+            sift_up(&mut self.items, &self.compare);
+            return;
+        }
+
+        match self.items.first() {
+            Some(least) if self.compare.compare(least, &item) == Ordering::Less => {
+                self.items[0] = item;
+                sift_down(&mut self.items, &self.compare);
+            }
+            _ => {}
+        }
+    }
+
+    /// Iterates over the kept items in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.items.iter()
+    }
+
+    /// The kept item the comparator ranks lowest, which is the next one to be
+    /// evicted: the smallest under [`Largest`], the largest under
+    /// [`Smallest`].
+    pub fn peek_next_evicted(&self) -> Option<&T> {
+        self.items.first()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.items.is_full()
+    }
+
+    /// Adds the items kept by `other`, as if they had been pushed into this
+    /// heap.
+    pub fn merge(&mut self, other: Self) {
+        self.extend(other.items)
+    }
+
+    /// The kept items, greatest first.
+    pub fn into_sorted_vec(mut self) -> heapless::Vec<T, N> {
+        let compare = &self.compare;
+        self.items.sort_unstable_by(|a, b| compare.compare(b, a));
+        self.items
+    }
+}
+
+impl<T, const N: usize, C: Compare<T> + Default> Default for ArrayLimitHeap<T, N, C> {
+    fn default() -> Self {
+        Self::with_comparator(C::default())
+    }
+}
+
+impl<T, const N: usize, C: Compare<T>> Extend<T> for ArrayLimitHeap<T, N, C> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
         for item in iter {
-            heap.push(item)
+            self.push(item)
         }
+    }
+}
 
+impl<A, const N: usize, C: Compare<A> + Default> FromIterator<A> for ArrayLimitHeap<A, N, C> {
+    fn from_iter<T: IntoIterator<Item = A>>(iter: T) -> Self {
+        let mut heap = Self::default();
+        heap.extend(iter);
         heap
     }
 }
 
+/// Collects in parallel, merging the heaps built on each thread. Like
+/// [`FromIterator`], this keeps `N` items, and `N` must not be 0.
+#[cfg(feature = "rayon")]
+impl<A, const N: usize, C> rayon::iter::FromParallelIterator<A> for ArrayLimitHeap<A, N, C>
+where
+    A: Send,
+    C: Compare<A> + Default + Send,
+{
+    fn from_par_iter<I>(par_iter: I) -> Self
+    where
+        I: rayon::iter::IntoParallelIterator<Item = A>,
+    {
+        use rayon::iter::ParallelIterator;
+
+        par_iter
+            .into_par_iter()
+            .fold(Self::default, |mut heap, item| {
+                heap.push(item);
+                heap
+            })
+            .reduce(Self::default, |mut heap, other| {
+                heap.merge(other);
+                heap
+            })
+    }
+}
+
+/// Yields the kept items, greatest first.
+impl<T, const N: usize, C: Compare<T>> IntoIterator for ArrayLimitHeap<T, N, C> {
+    type Item = T;
+    type IntoIter = <heapless::Vec<T, N> as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.into_sorted_vec().into_iter()
+    }
+}
+
 #[cfg(test)]
 mod test {
+    use itertools::Itertools;
+    use proptest::prelude::*;
+
+    use super::{ArrayLimitHeap, LimitHeap};
+
     #[test]
     fn limits_amount() {
-        let mut heap = super::LimitHeap::<u32, 3>::new();
+        let mut heap = LimitHeap::<u32, 3>::new();
 
         heap.push(2);
         heap.push(3);
@@ -51,6 +458,128 @@ mod test {
         heap.push(5);
         heap.push(1);
 
-        assert_eq!(heap.iter().copied().collect::<Vec<_>>(), vec![3, 4, 5])
+        let mut items: Vec<_> = heap.iter().copied().collect();
+        items.sort();
+        assert_eq!(items, vec![3, 4, 5])
+    }
+
+    #[test]
+    fn runtime_capacity() {
+        let mut heap = LimitHeap::with_capacity(2);
+        assert!(heap.is_empty());
+
+        heap.extend([2, 3, 4, 1]);
+
+        assert!(heap.is_full());
+        assert_eq!(heap.len(), 2);
+        assert_eq!(heap.capacity(), 2);
+        assert_eq!(heap.peek_next_evicted(), Some(&3));
+        assert_eq!(heap.into_sorted_vec(), vec![4, 3]);
+    }
+
+    #[test]
+    fn smallest() {
+        let mut heap = LimitHeap::smallest(2);
+        heap.extend([2, 3, 4, 1]);
+
+        assert_eq!(heap.peek_next_evicted(), Some(&2));
+        assert_eq!(heap.into_sorted_vec(), vec![1, 2]);
+    }
+
+    #[test]
+    fn by_key() {
+        let mut heap = LimitHeap::by_key(2, |(_, count): &(&str, u32)| *count);
+        heap.extend([("a", 2), ("b", 3), ("c", 4), ("d", 1)]);
+
+        assert_eq!(heap.into_sorted_vec(), vec![("c", 4), ("b", 3)]);
+    }
+
+    #[test]
+    fn by() {
+        let mut heap = LimitHeap::by(2, |a: &&str, b: &&str| a.len().cmp(&b.len()));
+        heap.extend(["aaa", "b", "cccc", "dd"]);
+
+        assert_eq!(heap.into_sorted_vec(), vec!["cccc", "aaa"]);
+    }
+
+    #[test]
+    fn array() {
+        let mut heap = ArrayLimitHeap::<_, 2, _>::by_key(|item: &i32| item.abs());
+        assert!(heap.is_empty());
+
+        heap.extend([2, -3, 4, 1]);
+
+        assert!(heap.is_full());
+        assert_eq!(heap.capacity(), 2);
+        assert_eq!(heap.peek_next_evicted(), Some(&-3));
+        assert_eq!(heap.into_sorted_vec(), [4, -3]);
+    }
+
+    #[test]
+    fn merge() {
+        let mut heap = LimitHeap::with_capacity(3);
+        heap.extend([5, 1, 3]);
+        let mut other = LimitHeap::with_capacity(3);
+        other.extend([4, 2, 6]);
+
+        heap.merge(other);
+
+        assert_eq!(heap.into_sorted_vec(), vec![6, 5, 4]);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_collect() {
+        use rayon::prelude::*;
+
+        let heap: LimitHeap<_, 3> = (0..100_000u32).into_par_iter().collect();
+        let array: ArrayLimitHeap<_, 3> = (0..100_000u32).into_par_iter().collect();
+
+        assert_eq!(heap.into_sorted_vec(), vec![99_999, 99_998, 99_997]);
+        assert_eq!(array.into_sorted_vec(), [99_999, 99_998, 99_997]);
+    }
+
+    proptest! {
+        #[test]
+        fn keeps_top_n_of_full_sort(items in prop::collection::vec(any::<u32>(), 0..100)) {
+            let heap: LimitHeap<_, 5> = items.iter().copied().collect();
+
+            let kept = heap.iter().copied().sorted().rev().collect_vec();
+            let expected = items.iter().copied().sorted().rev().take(5).collect_vec();
+            prop_assert_eq!(kept, expected);
+        }
+
+        #[test]
+        fn array_matches_vec(items in prop::collection::vec(any::<u32>(), 0..100)) {
+            let array: ArrayLimitHeap<_, 5> = items.iter().copied().collect();
+            let vec: LimitHeap<_, 5> = items.iter().copied().collect();
+
+            prop_assert_eq!(array.into_iter().collect_vec(), vec.into_iter().collect_vec());
+        }
+
+        #[test]
+        fn keeps_top_k_of_full_sort(
+            items in prop::collection::vec(any::<u32>(), 0..100),
+            k in 0..10usize,
+        ) {
+            let mut heap = LimitHeap::with_capacity(k);
+            heap.extend(items.iter().copied());
+
+            let expected = items.iter().copied().sorted().rev().take(k).collect_vec();
+            prop_assert_eq!(heap.peek_next_evicted(), expected.last());
+            prop_assert_eq!(heap.into_iter().collect_vec(), expected);
+        }
+
+        #[test]
+        fn keeps_bottom_k_of_full_sort(
+            items in prop::collection::vec(any::<u32>(), 0..100),
+            k in 0..10usize,
+        ) {
+            let mut heap = LimitHeap::smallest(k);
+            heap.extend(items.iter().copied());
+
+            let expected = items.iter().copied().sorted().take(k).collect_vec();
+            prop_assert_eq!(heap.into_iter().collect_vec(), expected);
+        }
     }
 }
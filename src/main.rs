@@ -1,107 +1,365 @@
-use std::{collections::HashMap, fmt::Display, fs::read_to_string, time::Instant};
+use std::{
+    env,
+    fs::{self, read_to_string},
+    io::{stdout, IsTerminal},
+    path::{Path, PathBuf},
+    process::exit,
+    thread,
+    time::{Duration, Instant},
+};
 
-use clap::Parser;
+use aoc_2022::{
+    analyze::Options,
+    day_five::{self, replay::Replay, Crane, CrateMover9000, CrateMover9001},
+    solutions, Error,
+};
+use clap::{Parser, Subcommand, ValueEnum};
 use itertools::Itertools;
+use table::Row;
 
-mod day_eight;
-mod day_five;
-mod day_four;
-mod day_nine;
-mod day_one;
-mod day_seven;
-mod day_six;
-mod day_ten;
-mod day_three;
-mod day_two;
-mod day_eleven;
-
-mod limit_heap;
-
-trait SolutionRunner {
-    fn part_one(&self, input: &str) -> String;
-    fn part_two(&self, input: &str) -> String;
-    fn day(&self) -> usize;
+mod table;
+
+#[derive(Parser, Debug)]
+#[command()]
+struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Day to run, all days are run if omitted
+    #[arg(short, long)]
+    day: Option<usize>,
+
+    /// Print the stacks day 5 ends with after each part
+    #[arg(long)]
+    draw_stacks: bool,
 }
 
-trait Solution {
-    const DAY: usize;
-    type O1: Display;
-    type O2: Display;
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Print a report about a day's input
+    Analyze {
+        day: usize,
 
-    fn part_one(input: &str) -> Self::O1;
+        /// Number of day 1 elves carrying the most calories to list
+        #[arg(long, default_value_t = Options::default().top)]
+        top: usize,
 
-    fn part_two(input: &str) -> Self::O2;
+        /// Number of rucksacks in each day 3 group
+        #[arg(long, default_value_t = Options::default().group_size)]
+        group_size: usize,
+
+        /// Number of compartments each day 3 rucksack is split into
+        #[arg(long, default_value_t = Options::default().compartments)]
+        compartments: usize,
+
+        /// Number of compartments a day 3 item must be in to count as shared
+        #[arg(long, default_value_t = Options::default().at_least)]
+        at_least: usize,
+    },
+    /// Replay day 5's procedure move by move, printing the stacks after the
+    /// last move replayed
+    Replay {
+        /// Crane carrying out the procedure
+        #[arg(long, value_enum, default_value_t = CraneModel::CrateMover9000)]
+        crane: CraneModel,
+
+        /// Number of moves made before the first frame
+        #[arg(long, default_value_t = 0)]
+        from: usize,
+
+        /// Number of moves made at the last frame, all moves if omitted. Frames
+        /// go backwards if it is before `--from`
+        #[arg(long)]
+        to: Option<usize>,
+
+        /// Write every frame to a text file in this directory
+        #[arg(long)]
+        frames: Option<PathBuf>,
+
+        /// Animate every frame in the terminal, waiting this many milliseconds
+        /// between them
+        #[arg(long)]
+        delay: Option<u64>,
+    },
+    /// Print the parsed model of a day's input
+    #[cfg(feature = "serde")]
+    Parse {
+        day: usize,
+
+        #[arg(long, value_enum, default_value_t = Emit::Json)]
+        emit: Emit,
+    },
 }
 
-impl<T: Solution> SolutionRunner for T {
-    fn part_one(&self, input: &str) -> String {
-        T::part_one(input).to_string()
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum CraneModel {
+    #[value(name = "9000")]
+    CrateMover9000,
+    #[value(name = "9001")]
+    CrateMover9001,
+}
+
+#[cfg(feature = "serde")]
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum Emit {
+    Json,
+}
+
+fn read_input(day: usize) -> String {
+    read_to_string(format!("./inputs/day{day}.txt"))
+        .unwrap_or_else(|_| panic!("No input file for day {day}"))
+}
+
+#[cfg(feature = "serde")]
+fn parse(day: usize, emit: Emit) {
+    let input = read_input(day);
+
+    let model = match emit {
+        Emit::Json => aoc_2022::emit::json(day, &input),
+    };
+
+    match model {
+        Ok(Some(model)) => println!("{model}"),
+        Ok(None) => {
+            eprintln!("Day {day} has no structured model");
+            exit(1)
+        }
+        Err(error) => {
+            eprintln!("{error}");
+            exit(1)
+        }
     }
+}
+
+fn analyze(day: usize, options: &Options) {
+    let input = read_input(day);
 
-    fn part_two(&self, input: &str) -> String {
-        T::part_two(input).to_string()
+    match aoc_2022::analyze::report(day, &input, options) {
+        Ok(Some(report)) => print!("{report}"),
+        Ok(None) => {
+            eprintln!("Day {day} has no analysis");
+            exit(1)
+        }
+        Err(error) => {
+            eprintln!("{error}");
+            exit(1)
+        }
     }
+}
 
-    fn day(&self) -> usize {
-        T::DAY
+/// Pushes the frames of the replay from move `from` to move `to` onto
+/// `frames`, stopping at the first move that fails.
+fn replay_frames(
+    crane: impl Crane,
+    input: &str,
+    from: usize,
+    to: Option<usize>,
+    frames: &mut Vec<String>,
+) -> Result<(), Error> {
+    let mut replay = Replay::from_input(crane, input)?;
+    let to = to.unwrap_or(replay.len());
+    if to > replay.len() {
+        return Err(Error::Invalid(format!(
+            "procedure has only {} moves",
+            replay.len()
+        )));
     }
+    replay.jump_to(from)?;
+
+    frames.push(replay.to_string());
+    while replay.position() != to {
+        if replay.position() < to {
+            replay.step_forward()?;
+        } else {
+            replay.step_back();
+        }
+        frames.push(replay.to_string());
+    }
+    Ok(())
 }
 
-fn create_solution_map<const N: usize>(
-    solutions: [&dyn SolutionRunner; N],
-) -> HashMap<usize, &dyn SolutionRunner> {
-    solutions.into_iter().map(|s| (s.day(), s)).collect()
+fn write_frames(directory: &Path, frames: &[String]) -> std::io::Result<()> {
+    fs::create_dir_all(directory)?;
+    for (index, frame) in frames.iter().enumerate() {
+        fs::write(directory.join(format!("frame-{index:04}.txt")), frame)?;
+    }
+    Ok(())
 }
 
-#[derive(Parser, Debug)]
-#[command()]
-struct Args {
-    /// Name of the person to greet
-    #[arg(short, long)]
-    day: Option<usize>,
+fn replay(
+    crane: CraneModel,
+    from: usize,
+    to: Option<usize>,
+    frames: Option<PathBuf>,
+    delay: Option<u64>,
+) {
+    let input = read_input(5);
+    let mut replayed = Vec::new();
+    let result = match crane {
+        CraneModel::CrateMover9000 => {
+            replay_frames(CrateMover9000, &input, from, to, &mut replayed)
+        }
+        CraneModel::CrateMover9001 => {
+            replay_frames(CrateMover9001, &input, from, to, &mut replayed)
+        }
+    };
+
+    if let Some(directory) = frames {
+        if let Err(error) = write_frames(&directory, &replayed) {
+            eprintln!("Failed to write frames to {}: {error}", directory.display());
+            exit(1)
+        }
+        println!("Wrote {} frames to {}", replayed.len(), directory.display());
+    } else if let Some(delay) = delay {
+        for frame in &replayed {
+            // Clears the terminal and moves the cursor to the top left.
+            print!("\x1b[2J\x1b[H{frame}");
+            thread::sleep(Duration::from_millis(delay));
+        }
+    } else if let Some(frame) = replayed.last() {
+        print!("{frame}");
+    }
+
+    if let Err(error) = result {
+        eprintln!("{error}");
+        exit(1)
+    }
 }
 
-fn main() {
-    let solutions = create_solution_map([
-        &day_one::Solution,
-        &day_two::Solution,
-        &day_three::Solution,
-        &day_four::Solution,
-        &day_five::Solution,
-        &day_six::Solution,
-        &day_seven::Solution,
-        &day_eight::Solution,
-        &day_nine::Solution,
-        &day_ten::Solution,
-        &day_eleven::Solution,
-    ]);
+/// Day 5's answer to a part, along with a drawing of the stacks it ends with.
+fn rearrange_and_draw(input: &str, crane: &impl Crane) -> (Result<String, Error>, Option<String>) {
+    match day_five::rearrange(input, crane) {
+        Ok(stacks) => (Ok(stacks.tops()), Some(stacks.to_string())),
+        Err(error) => (Err(error), None),
+    }
+}
 
-    let args = Args::parse();
+fn run(days: Option<usize>, draw: bool) {
+    let solutions = solutions();
 
-    let days_to_run = match args.day {
+    let days_to_run = match days {
         Some(day) => vec![day],
         None => solutions.keys().copied().sorted().collect(),
     };
 
-    for day in days_to_run {
-        let solution = solutions
-            .get(&day)
-            .expect(&format!("No solution for day {day}"));
+    let mut rows = Vec::new();
+    for &day in &days_to_run {
+        let Some(solution) = solutions.get(&day) else {
+            eprintln!("No solution for day {day}");
+            exit(1)
+        };
 
-        let input = read_to_string(format!("./inputs/day{day}.txt"))
-            .expect(&format!("No input file for day {day}"));
+        let input = read_input(day);
         let input = input.as_str();
 
-        println!("Day {day}");
+        let draw = draw && day == 5;
+
         let start = Instant::now();
-        let answer = solution.part_one(input);
-        let duration = start.elapsed().as_micros();
-        println!("  Part one: {answer} ({duration}us)");
+        let (answer, drawing) = if draw {
+            rearrange_and_draw(input, &CrateMover9000)
+        } else {
+            (solution.part_one(input), None)
+        };
+        rows.push(Row {
+            day,
+            part: "one",
+            answer: answer.map_err(|error| error.to_string()),
+            drawing,
+            time: start.elapsed(),
+        });
 
         let start = Instant::now();
-        let answer = solution.part_two(input);
-        let duration = start.elapsed().as_micros();
-        println!("  Part two: {answer} ({duration}us)");
-        println!()
+        let (answer, drawing) = if draw {
+            rearrange_and_draw(input, &CrateMover9001)
+        } else {
+            (solution.part_two(input), None)
+        };
+        rows.push(Row {
+            day,
+            part: "two",
+            answer: answer.map_err(|error| error.to_string()),
+            drawing,
+            time: start.elapsed(),
+        });
+    }
+
+    print!("{}", table::render(&rows, colour_enabled()));
+}
+
+/// Colours are only used on a terminal, and never if `NO_COLOR` is set.
+fn colour_enabled() -> bool {
+    let no_color = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+    stdout().is_terminal() && !no_color
+}
+
+fn main() {
+    let args = Args::parse();
+
+    match args.command {
+        Some(Command::Analyze {
+            day,
+            top,
+            group_size,
+            compartments,
+            at_least,
+        }) => analyze(
+            day,
+            &Options {
+                top,
+                group_size,
+                compartments,
+                at_least,
+            },
+        ),
+        Some(Command::Replay {
+            crane,
+            from,
+            to,
+            frames,
+            delay,
+        }) => replay(crane, from, to, frames, delay),
+        #[cfg(feature = "serde")]
+        Some(Command::Parse { day, emit }) => parse(day, emit),
+        None => run(args.day, args.draw_stacks),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use aoc_2022::{day_five::CrateMover9000, Error};
+    use indoc::indoc;
+
+    #[test]
+    fn replay_frames_until_failure() {
+        let input = indoc! {"
+            [A]    
+             1   2 
+
+            move 1 from 1 to 2
+            move 1 from 2 to 1
+            move 2 from 1 to 2
+            move 1 from 1 to 2
+        "};
+
+        let mut frames = Vec::new();
+        let result = super::replay_frames(CrateMover9000, input, 0, None, &mut frames);
+
+        assert_eq!(
+            result,
+            Err(Error::Invalid(
+                "move 3: stack 1 has too few crates to move".to_string()
+            ))
+        );
+        assert_eq!(
+            frames
+                .iter()
+                .map(|frame| frame.lines().next().unwrap())
+                .collect::<Vec<_>>(),
+            [
+                "Move 0/4: start",
+                "Move 1/4: move 1 from 1 to 2",
+                "Move 2/4: move 1 from 2 to 1",
+            ]
+        );
     }
 }
@@ -0,0 +1,207 @@
+use std::{collections::BTreeMap, fmt::Write, time::Duration};
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const YELLOW: &str = "\x1b[33m";
+
+pub struct Row {
+    pub day: usize,
+    pub part: &'static str,
+    pub answer: Result<String, String>,
+    /// Drawn under the answer, without widening the answer column.
+    pub drawing: Option<String>,
+    pub time: Duration,
+}
+
+impl Row {
+    /// Lines of the answer, without the blank lines multi-line answers are
+    /// padded with.
+    fn lines(&self) -> Vec<&str> {
+        let answer = match &self.answer {
+            Ok(answer) => answer,
+            Err(error) => error,
+        };
+        answer.trim_matches('\n').lines().collect()
+    }
+}
+
+fn format_time(time: Duration) -> String {
+    if time < Duration::from_millis(1) {
+        format!("{}µs", time.as_micros())
+    } else if time < Duration::from_secs(1) {
+        format!("{:.2}ms", time.as_secs_f64() * 1000.0)
+    } else {
+        format!("{:.2}s", time.as_secs_f64())
+    }
+}
+
+fn time_colour(time: Duration) -> &'static str {
+    if time < Duration::from_millis(1) {
+        GREEN
+    } else if time < Duration::from_secs(1) {
+        YELLOW
+    } else {
+        RED
+    }
+}
+
+/// Renders the rows as a table, using ANSI colours if `colour` is set.
+pub fn render(rows: &[Row], colour: bool) -> String {
+    let paint = |text: &str, code: &str| {
+        if colour {
+            format!("{code}{text}{RESET}")
+        } else {
+            text.to_string()
+        }
+    };
+
+    let mut days = BTreeMap::new();
+    for row in rows {
+        *days.entry(row.day).or_insert(Duration::ZERO) += row.time;
+    }
+    let total: Duration = days.values().sum();
+    let summary = match days.iter().max_by_key(|(_, time)| **time) {
+        Some((day, time)) => format!("slowest: day {day} ({})", format_time(*time)),
+        None => String::new(),
+    };
+
+    let answer_width = rows
+        .iter()
+        .flat_map(Row::lines)
+        .chain([summary.as_str(), "Answer"])
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0);
+    let time_width = rows
+        .iter()
+        .map(|row| row.time)
+        .chain([total])
+        .map(|time| format_time(time).chars().count())
+        .max()
+        .unwrap_or(0)
+        .max("Time".len());
+
+    let mut table = String::new();
+    let header = format!(
+        "{:>3}  {:<4}  {:<answer_width$}  {:>time_width$}  Status",
+        "Day", "Part", "Answer", "Time"
+    );
+    writeln!(table, "{}", paint(&header, BOLD)).unwrap();
+    writeln!(
+        table,
+        "{}  {}  {}  {}  {}",
+        "─".repeat(3),
+        "─".repeat(4),
+        "─".repeat(answer_width),
+        "─".repeat(time_width),
+        "─".repeat(6)
+    )
+    .unwrap();
+
+    for row in rows {
+        let lines = row.lines();
+        let first = lines.first().copied().unwrap_or_default();
+        let time = format!("{:>time_width$}", format_time(row.time));
+        let status = match row.answer {
+            Ok(_) => paint("ok", GREEN),
+            Err(_) => paint("error", RED),
+        };
+
+        writeln!(
+            table,
+            "{:>3}  {:<4}  {first:<answer_width$}  {}  {status}",
+            row.day,
+            row.part,
+            paint(&time, time_colour(row.time)),
+        )
+        .unwrap();
+
+        let drawing = row
+            .drawing
+            .iter()
+            .flat_map(|drawing| drawing.lines().map(str::trim_end));
+        for line in lines.iter().copied().skip(1).chain(drawing) {
+            writeln!(table, "{:>3}  {:<4}  {line}", "", "").unwrap();
+        }
+    }
+
+    let total = format!("{:>time_width$}", format_time(total));
+    let summary = format!("{:<3}  {:<4}  {summary:<answer_width$}  {total}", "All", "");
+    writeln!(table, "{}", paint(summary.trim_end(), BOLD)).unwrap();
+
+    table
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use indoc::indoc;
+
+    use super::Row;
+
+    #[test]
+    fn render() {
+        let rows = [
+            Row {
+                day: 1,
+                part: "one",
+                answer: Ok("42".to_string()),
+                drawing: None,
+                time: Duration::from_micros(15),
+            },
+            Row {
+                day: 10,
+                part: "two",
+                answer: Ok("\n\n# #\n ##\n\n".to_string()),
+                drawing: None,
+                time: Duration::from_millis(1500),
+            },
+            Row {
+                day: 11,
+                part: "one",
+                answer: Err("Invalid puzzle".to_string()),
+                drawing: None,
+                time: Duration::from_micros(2500),
+            },
+        ];
+
+        assert_eq!(
+            super::render(&rows, false),
+            indoc! {"
+                Day  Part  Answer                     Time  Status
+                ───  ────  ───────────────────────  ──────  ──────
+                  1  one   42                         15µs  ok
+                 10  two   # #                       1.50s  ok
+                            ##
+                 11  one   Invalid puzzle           2.50ms  error
+                All        slowest: day 10 (1.50s)   1.50s
+            "}
+        );
+    }
+
+    #[test]
+    fn render_drawing() {
+        let rows = [Row {
+            day: 5,
+            part: "one",
+            answer: Ok("ABCDEF".to_string()),
+            drawing: Some("[A] [B] [C] [D] [E] [F]\n 1   2   3   4   5   6 \n".to_string()),
+            time: Duration::from_micros(15),
+        }];
+
+        assert_eq!(
+            super::render(&rows, false),
+            indoc! {"
+                Day  Part  Answer                 Time  Status
+                ───  ────  ─────────────────────  ────  ──────
+                  5  one   ABCDEF                 15µs  ok
+                           [A] [B] [C] [D] [E] [F]
+                            1   2   3   4   5   6
+                All        slowest: day 5 (15µs)  15µs
+            "}
+        );
+    }
+}